@@ -1,46 +1,47 @@
-static INPUT: &str = include_str!("input.txt");
-
-pub fn part_one() -> u32 {
-    INPUT
-        .split("\n\n")
-        .map(|block| {
-            block
-                .split('\n')
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .max()
-        .unwrap()
-}
+use crate::{
+    solution::{Answer, Solution},
+    util::error::{self, Error},
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    /// Calories carried per elf.
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        input
+            .split("\n\n")
+            .map(|block| block.split('\n').map(error::number::<u32>).sum())
+            .collect()
+    }
+
+    fn part_one(block_values: &Self::Parsed) -> Answer {
+        block_values.iter().max().copied().unwrap().into()
+    }
 
-pub fn part_two() -> u32 {
-    let mut block_values: Vec<u32> = INPUT
-        .split("\n\n")
-        .map(|block| {
-            block
-                .split('\n')
-                .map(|line| line.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .collect();
+    fn part_two(block_values: &Self::Parsed) -> Answer {
+        let mut block_values = block_values.clone();
 
-    block_values.sort_by(|a, b| b.cmp(a));
+        block_values.sort_by(|a, b| b.cmp(a));
 
-    block_values[0..3].iter().sum()
+        block_values[0..3].iter().sum::<u32>().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::part_one;
-    use super::part_two;
+    use super::Day1;
+    use crate::solution::{Answer, Part, Solution};
+    static INPUT: &str = include_str!("input.txt");
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(), 71934)
+        assert_eq!(Day1::solve(INPUT, Part::One), Ok(Answer::from(71934)))
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(), 211447)
+        assert_eq!(Day1::solve(INPUT, Part::Two), Ok(Answer::from(211447)))
     }
 }
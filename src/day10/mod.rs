@@ -1,26 +1,30 @@
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::error::{self, Error},
+};
 
-enum Cmd {
+#[derive(Clone, Copy)]
+pub enum Cmd {
     Noop,
     Add(i32),
 }
 
-fn parse_commands<'a>(input: &'a str) -> impl Iterator<Item = Cmd> + 'a {
-    return input.lines().map(|line| {
+fn parse_commands<'a>(input: &'a str) -> impl Iterator<Item = Result<Cmd, Error>> + 'a {
+    input.lines().map(|line| {
         let mut parts = line.split_whitespace();
 
-        match (parts.next().unwrap(), parts.next()) {
-            ("noop", None) => Cmd::Noop,
-            ("addx", Some(amount)) => Cmd::Add(amount.parse::<i32>().unwrap()),
-            _ => unreachable!(),
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("noop"), None, _) => Ok(Cmd::Noop),
+            (Some("addx"), Some(amount), None) => Ok(Cmd::Add(error::number(amount)?)),
+            _ => Err(Error::at(line, format!("unknown command `{line}`"))),
         }
-    });
+    })
 }
 
-pub fn part_one() -> i32 {
+fn signal_strength_sum(commands: &[Cmd]) -> i32 {
     let mut total = 0;
 
-    let mut commands = parse_commands(INPUT);
+    let mut commands = commands.iter().copied();
 
     let mut x = 1;
     let mut cycles = 1;
@@ -49,13 +53,11 @@ pub fn part_one() -> i32 {
         cycles += 1;
     }
 
-    println!("TOTAL {:?}", total);
-
-    return total;
+    total
 }
 
-pub fn part_two() {
-    let mut commands = parse_commands(INPUT);
+fn render_screen(commands: &[Cmd]) -> String {
+    let mut commands = commands.iter().copied();
 
     let mut screen = vec![vec![false; 40]; 6];
 
@@ -90,14 +92,38 @@ pub fn part_two() {
         cycle += 1;
     }
 
-    for y in 0..screen.len() {
-        for x in 0..screen[y].len() {
-            if screen[y][x] {
-                print!("#")
+    let mut output = String::with_capacity(41 * 6);
+    for (y, row) in screen.iter().enumerate() {
+        if y > 0 {
+            output.push('\n');
+        }
+
+        for pixel in row {
+            if *pixel {
+                output.push('#')
             } else {
-                print!(".")
+                output.push('.')
             }
         }
-        println!("")
+    }
+
+    output
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Vec<Cmd>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        parse_commands(input).collect()
+    }
+
+    fn part_one(commands: &Self::Parsed) -> Answer {
+        signal_strength_sum(commands).into()
+    }
+
+    fn part_two(commands: &Self::Parsed) -> Answer {
+        render_screen(commands).into()
     }
 }
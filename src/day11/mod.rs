@@ -1,68 +1,40 @@
-use std::{collections::VecDeque, fmt::Error, num::ParseIntError, str::FromStr};
+use std::collections::VecDeque;
 
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        parse::{self, Parser},
+        Error,
+    },
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Operator {
     Plus,
     Multiply,
 }
 
-impl FromStr for Operator {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "+" => Ok(Operator::Plus),
-            "*" => Ok(Operator::Multiply),
-            _ => Err(String::from("Unknown operator")),
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operand {
     Current,
     Value(u128),
 }
 
-impl FromStr for Operand {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "old" => Ok(Operand::Current),
-            value => match value.parse() {
-                Err(err) => Err(err),
-                Ok(value) => Ok(Operand::Value(value)),
-            },
+impl Operand {
+    fn parse(p: &mut Parser) -> Result<Self, Error> {
+        match p.try_literal("old") {
+            true => Ok(Operand::Current),
+            false => Ok(Operand::Value(p.unsigned()?)),
         }
     }
 }
 
-#[derive(Debug)]
-struct Operation {
+#[derive(Debug, Clone)]
+pub struct Operation {
     operator: Operator,
     operands: (Operand, Operand),
 }
 
-impl FromStr for Operation {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split_whitespace();
-
-        let first_operand = parts.next().unwrap().parse::<Operand>().unwrap();
-        let operator = parts.next().unwrap().parse::<Operator>().unwrap();
-        let second_operand = parts.next().unwrap().parse::<Operand>().unwrap();
-
-        Ok(Operation {
-            operator,
-            operands: (first_operand, second_operand),
-        })
-    }
-}
-
 impl Operation {
     pub fn execute(&self, input: u128) -> u128 {
         let first_value = match self.operands.0 {
@@ -81,32 +53,13 @@ impl Operation {
     }
 }
 
-#[derive(Debug)]
-struct Test {
+#[derive(Debug, Clone)]
+pub struct Test {
     pub test: u128,
     pub monkey_on_true: usize,
     pub monkey_on_false: usize,
 }
 
-impl FromStr for Test {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<_> = s.lines().collect();
-
-        // TODO: Beautify
-        let test: u128 = lines[0].split_whitespace().last().unwrap().parse().unwrap();
-        let monkey_on_true: usize = lines[1].split_whitespace().last().unwrap().parse().unwrap();
-        let monkey_on_false: usize = lines[2].split_whitespace().last().unwrap().parse().unwrap();
-
-        Ok(Test {
-            test,
-            monkey_on_true,
-            monkey_on_false,
-        })
-    }
-}
-
 impl Test {
     pub fn get_monkey_for_input(&self, input: u128) -> usize {
         match input % self.test {
@@ -116,42 +69,76 @@ impl Test {
     }
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone)]
+pub struct Monkey {
     pub inspections: usize,
     pub items: VecDeque<u128>,
     pub operation: Operation,
     pub test: Test,
 }
 
-impl FromStr for Monkey {
-    type Err = Error;
+/// The monkey another one throws to, which has to be one of the `count` others.
+fn target(p: &mut Parser, monkey: usize, count: usize) -> Result<usize, Error> {
+    let token = p.rest();
+    let target = p.unsigned()?;
+
+    match target {
+        target if target == monkey => Err(Error::at(token, "a monkey cannot throw to itself")),
+        target if target >= count => Err(Error::at(
+            token,
+            format!("there is no monkey {target}, only {count}"),
+        )),
+        target => Ok(target),
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split("Test");
+impl Monkey {
+    /// Monkey number `monkey` of `count`, which all have to be numbered in order.
+    fn parse(s: &str, monkey: usize, count: usize) -> Result<Self, Error> {
+        let mut p = Parser::new(s);
+
+        p.literal("Monkey")?;
+        let token = p.rest();
+        if p.unsigned::<usize>()? != monkey {
+            return Err(Error::at(token, format!("expected monkey {monkey}")));
+        }
+        p.literal(":")?;
 
-        let upper_lines: Vec<_> = parts.next().unwrap().lines().collect();
+        p.literal("Starting items:")?;
+        let items = p.separated(",", Parser::unsigned)?;
 
-        let items: VecDeque<u128> = upper_lines[1]
-            .split_once(':')
-            .unwrap()
-            .1
-            .split(',')
-            .map(|item| item.trim().parse::<u128>().unwrap())
-            .collect();
+        p.literal("Operation: new =")?;
+        let first_operand = Operand::parse(&mut p)?;
+        let operator = p.one_of(&[("+", Operator::Plus), ("*", Operator::Multiply)])?;
+        let second_operand = Operand::parse(&mut p)?;
 
-        let operation_input = upper_lines[2].split_once('=').unwrap().1.trim();
+        p.literal("Test: divisible by")?;
+        let token = p.rest();
+        let test = p.unsigned()?;
+        if test == 0 {
+            return Err(Error::at(token, "nothing is divisible by 0"));
+        }
+        p.literal("If true: throw to monkey")?;
+        let monkey_on_true = target(&mut p, monkey, count)?;
+        p.literal("If false: throw to monkey")?;
+        let monkey_on_false = target(&mut p, monkey, count)?;
+        p.end()?;
 
         Ok(Monkey {
             inspections: 0,
-            items,
-            operation: operation_input.parse().unwrap(),
-            test: parts.next().unwrap().parse().unwrap(),
+            items: VecDeque::from(items),
+            operation: Operation {
+                operator,
+                operands: (first_operand, second_operand),
+            },
+            test: Test {
+                test,
+                monkey_on_true,
+                monkey_on_false,
+            },
         })
     }
-}
 
-impl Monkey {
     pub fn inspect_next_item(&mut self) -> Option<(usize, u128)> {
         let item = self.items.pop_front();
         match item {
@@ -172,13 +159,10 @@ impl Monkey {
     }
 }
 
-pub fn monkey_business(input: &str, rounds: usize) -> usize {
-    let mut monkeys: Vec<_> = input
-        .split("\n\n")
-        .map(|part| part.parse::<Monkey>().unwrap())
-        .collect();
+fn monkey_business(monkeys: &[Monkey], rounds: usize) -> usize {
+    let mut monkeys = monkeys.to_vec();
 
-    println!("{:?}", monkeys);
+    crate::debug!("{:?}", monkeys);
 
     for _ in 0..rounds {
         for i in 0..monkeys.len() {
@@ -196,15 +180,29 @@ pub fn monkey_business(input: &str, rounds: usize) -> usize {
 
     monkeys.sort_by_key(|m| m.inspections);
 
-    return monkeys
+    monkeys
         .iter()
         .rev()
         .take(2)
         .map(|m| m.inspections)
-        .product();
+        .product()
 }
 
-pub fn part_one() {
-    let result_product = monkey_business(INPUT, 20);
-    println!("{:?}", result_product);
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let count = parse::blocks(input).count();
+
+        parse::blocks(input)
+            .enumerate()
+            .map(|(monkey, block)| Monkey::parse(block, monkey, count))
+            .collect()
+    }
+
+    fn part_one(monkeys: &Self::Parsed) -> Answer {
+        monkey_business(monkeys, 20).into()
+    }
 }
@@ -1,21 +1,69 @@
-use std::vec;
+use std::{cmp::Ordering, slice};
 
-static INPUT: &'static str = include_str!("input-test.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        error::{self, Error},
+        parse,
+    },
+};
 
 #[derive(Debug)]
-enum Element {
-    Value(u8),
+pub enum Element {
+    Value(u32),
     Array(Vec<Element>),
 }
 
-fn parse_array(arr: &str) -> Vec<Element> {
+/**
+ * Values compare by number and arrays element by element, the shorter array is smaller if
+ * they agree until then. A value compared to an array is treated as an array of just that
+ * value, so `1` and `[1]` are equal.
+ */
+impl Ord for Element {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Element::Value(left), Element::Value(right)) => left.cmp(right),
+            (Element::Array(left), Element::Array(right)) => left.cmp(right),
+            (Element::Value(_), Element::Array(right)) => slice::from_ref(self).cmp(right),
+            (Element::Array(left), Element::Value(_)) => {
+                left.as_slice().cmp(slice::from_ref(other))
+            }
+        }
+    }
+}
+
+impl PartialOrd for Element {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Element {}
+
+fn parse_array(arr: &str) -> Result<Vec<Element>, Error> {
+    if !arr.starts_with('[') {
+        return Err(Error::at(arr, "a packet has to start with `[`"));
+    }
+
+    let mut closed = false;
     let mut vector_stack: Vec<Vec<Element>> = vec![];
     let mut current_vec: Vec<Element> = vec![];
-    let mut current_value: Option<u8> = None;
+    let mut current_value: Option<u32> = None;
+    let mut value_start = 0;
 
     // Skip first '['
-    let chars = arr.chars().skip(1);
-    for character in chars {
+    let chars = arr.char_indices().skip(1);
+    for (index, character) in chars {
+        if closed {
+            return Err(Error::at(&arr[index..], "unexpected data after the packet"));
+        }
+
         match character {
             '[' => {
                 vector_stack.push(current_vec);
@@ -33,7 +81,7 @@ fn parse_array(arr: &str) -> Vec<Element> {
 
                 match vector_stack.pop() {
                     // We reached the end of the line
-                    None => {}
+                    None => closed = true,
                     Some(mut vector) => {
                         vector.push(Element::Array(current_vec));
                         current_vec = vector;
@@ -49,31 +97,152 @@ fn parse_array(arr: &str) -> Vec<Element> {
                 }
             },
             value => {
-                let digit = value.to_digit(10).unwrap() as u8;
-                current_value = Some(current_value.unwrap_or(0) * 10 + digit);
+                let digit = value.to_digit(10).ok_or_else(|| {
+                    Error::at(&arr[index..], format!("unexpected `{value}` in packet"))
+                })?;
+                if current_value.is_none() {
+                    value_start = index;
+                }
+                let value = current_value
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit))
+                    .ok_or_else(|| Error::at(&arr[value_start..], "the value is too large"))?;
+                current_value = Some(value);
             }
         }
     }
 
-    return current_vec;
+    if !closed {
+        return Err(Error::at(error::end_of(arr), "expected `]`"));
+    }
+
+    Ok(current_vec)
 }
 
-fn parse(pair: &str) -> (Vec<Element>, Vec<Element>) {
-    let (left, right) = pair.split_once('\n').unwrap();
-    let left = parse_array(left);
-    let right = parse_array(right);
+fn parse_packet(packet: &str) -> Result<Element, Error> {
+    parse_array(packet).map(Element::Array)
+}
+
+fn parse_pair(pair: &str) -> Result<(Element, Element), Error> {
+    let (left, right) = error::split_once(pair, "\n")?;
+    let left = parse_packet(left)?;
+    let right = parse_packet(right)?;
+
+    Ok((left, right))
+}
 
-    (left, right)
+fn pair_in_order((left, right): &(Element, Element)) -> bool {
+    left < right
 }
 
-fn pair_in_order(pair: &(Vec<Element>, Vec<Element>)) -> bool {
-    return true;
+/// The product of the positions of `[[2]]` and `[[6]]` among all sorted packets.
+fn decoder_key(pairs: &[(Element, Element)]) -> usize {
+    let dividers = [
+        parse_packet("[[2]]").unwrap(),
+        parse_packet("[[6]]").unwrap(),
+    ];
+
+    let mut packets: Vec<&Element> = pairs
+        .iter()
+        .flat_map(|(left, right)| [left, right])
+        .chain(&dividers)
+        .collect();
+    packets.sort();
+
+    dividers
+        .iter()
+        .map(|divider| packets.partition_point(|packet| *packet < divider) + 1)
+        .product()
 }
 
-pub fn part_one() {
-    let pairs = INPUT.split("\n\n").map(|block| parse(block));
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<(Element, Element)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        parse::blocks(input).map(parse_pair).collect()
+    }
+
+    fn part_one(pairs: &Self::Parsed) -> Answer {
+        pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, pair)| pair_in_order(pair))
+            .map(|(i, _)| i + 1)
+            .sum::<usize>()
+            .into()
+    }
+
+    fn part_two(pairs: &Self::Parsed) -> Answer {
+        decoder_key(pairs).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_packet, Day13};
+    use crate::solution::{Answer, Part, Solution};
+
+    static INPUT_TEST: &str = include_str!("input-test.txt");
+    static INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn test_ordering() {
+        let packet = |s| parse_packet(s).unwrap();
+
+        assert!(packet("[1,1,3,1,1]") < packet("[1,1,5,1,1]"));
+        assert!(packet("[[1],[2,3,4]]") < packet("[[1],4]"));
+        assert!(packet("[9]") > packet("[[8,7,6]]"));
+        assert!(packet("[7,7,7,7]") > packet("[7,7,7]"));
+        assert!(packet("[]") < packet("[3]"));
+        assert!(packet("[[[]]]") > packet("[[]]"));
+        assert_eq!(packet("[[1],2]"), packet("[1,[2]]"));
+    }
+
+    #[test]
+    fn test_multi_digit_values() {
+        let packet = |s| parse_packet(s).unwrap();
+
+        assert!(packet("[10]") > packet("[9]"));
+        assert!(packet("[1,10]") > packet("[1,2,3]"));
+        assert_eq!(packet("[[10]]"), packet("[10]"));
+        assert!(packet("[10,0]") < packet("[[10,1]]"));
+        assert!(packet("[256]") > packet("[255]"));
+        assert!(packet("[4294967295]") > packet("[[1000]]"));
+    }
+
+    #[test]
+    fn test_too_large_value() {
+        let input = "[1,[2]]\n[3,4294967296]";
+        let err = Day13::solve(input, Part::One).unwrap_err();
+
+        assert_eq!(err.message, "the value is too large");
+        let position = err.position.unwrap();
+        assert_eq!((position.line, position.column), (2, 4));
+    }
+
+    #[test]
+    fn test_part_one_test_data() {
+        assert_eq!(Day13::solve(INPUT_TEST, Part::One), Ok(Answer::from(13u64)))
+    }
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(Day13::solve(INPUT, Part::One), Ok(Answer::from(5659u64)))
+    }
+
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(
+            Day13::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(140u64))
+        )
+    }
 
-    for (i, pair) in pairs.enumerate() {
-        println!("{:?}:{:?}", i, pair_in_order(&pair));
+    #[test]
+    fn test_part_two() {
+        assert_eq!(Day13::solve(INPUT, Part::Two), Ok(Answer::from(22110u64)))
     }
 }
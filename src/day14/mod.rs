@@ -1,9 +1,17 @@
-use std::{num::ParseIntError, str::FromStr};
-
-static INPUT: &'static str = include_str!("input.txt");
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Tile {
+use std::{fmt, str::FromStr};
+
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        geom::{Point, Vector},
+        sparse::SparseGrid,
+        Error,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Tile {
+    #[default]
     Air,
     Rock,
     Sand,
@@ -11,238 +19,218 @@ enum Tile {
 }
 
 impl Tile {
-    pub fn draw(&self) {
+    pub fn symbol(&self) -> char {
         match self {
-            Tile::Air => print!("."),
-            Tile::Rock => print!("#"),
-            Tile::Sand => print!("o"),
-            Tile::Start => print!("+"),
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+            Tile::Start => '+',
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl FromStr for Point {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.trim().split_once(',').unwrap();
-
-        Ok(Point {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        })
-    }
-}
+/// Straight down first, then diagonally to the left and to the right.
+const FALLS: [Vector<i64>; 3] = [Vector::new(0, 1), Vector::new(-1, 1), Vector::new(1, 1)];
 
-struct Grid {
-    pub grid: Vec<Vec<Tile>>,
-    pub sand_start: Point,
-    pub lowest_rock_y: usize,
-    min_x: usize,
-    max_x: usize,
+#[derive(Clone)]
+pub struct Cave {
+    pub grid: SparseGrid<Tile>,
+    pub sand_start: Point<i64>,
+    pub lowest_rock_y: i64,
+    /// Only there in part two, as wide as the sand needs it.
+    floor: Option<i64>,
 }
 
-impl FromStr for Grid {
-    type Err = ParseIntError;
+impl FromStr for Cave {
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let rock_points = s
             .split('\n')
             .map(|line| {
-                line.split("->")
-                    .map(|step| step.parse::<Point>().unwrap())
-                    .collect::<Vec<_>>()
+                let points = line
+                    .split("->")
+                    .map(|token| Ok((token.trim(), token.parse()?)))
+                    .collect::<Result<Vec<(&str, Point<i64>)>, Error>>()?;
+
+                // Walking the path below only reaches the next point in a straight line.
+                for pair in points.windows(2) {
+                    let ((_, start), (token, end)) = (pair[0], pair[1]);
+                    if start.x != end.x && start.y != end.y {
+                        return Err(Error::at(
+                            token,
+                            format!("the path from {start} to {end} is not straight"),
+                        ));
+                    }
+                }
+
+                Ok(points.into_iter().map(|(_, point)| point).collect())
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<Vec<Point<i64>>>, Error>>()?;
 
-        let max_x = rock_points
-            .iter()
-            .flat_map(|f| f)
-            .map(|point| point.x)
-            .max()
-            .unwrap();
-        let min_x = rock_points
-            .iter()
-            .flat_map(|f| f)
-            .map(|point| point.x)
-            .min()
-            .unwrap();
         let max_y = rock_points
             .iter()
-            .flat_map(|f| f)
+            .flatten()
             .map(|point| point.y)
             .max()
             .unwrap();
 
-        let max_x = max_x + 200;
-
-        // TODO: Try optimization to not create 700 empty spaces
-        let mut grid: Vec<Vec<Tile>> = vec![vec![Tile::Air; max_x]; max_y + 4];
+        let mut grid = SparseGrid::new();
 
         for line in rock_points {
             for pair in line.windows(2) {
-                let left = &pair[0];
-                let right = &pair[1];
+                let (mut point, end) = (pair[0], pair[1]);
+                let step = Vector::new((end.x - point.x).signum(), (end.y - point.y).signum());
 
-                if right.x > left.x {
-                    for x in left.x..=right.x {
-                        grid[left.y][x] = Tile::Rock;
-                    }
-                    continue;
-                } else if left.x > right.x {
-                    for x in right.x..=left.x {
-                        grid[left.y][x] = Tile::Rock;
-                    }
-                    continue;
-                }
-
-                if right.y > left.y {
-                    for y in left.y..=right.y {
-                        grid[y][left.x] = Tile::Rock;
-                    }
-                } else if left.y > right.y {
-                    for y in right.y..=left.y {
-                        grid[y][left.x] = Tile::Rock;
-                    }
+                grid.set(point.x, point.y, Tile::Rock);
+                while point != end {
+                    point += step;
+                    grid.set(point.x, point.y, Tile::Rock);
                 }
             }
         }
 
-        grid[0][500] = Tile::Start;
+        grid.set(500, 0, Tile::Start);
 
-        Ok(Grid {
+        Ok(Cave {
             grid,
-            sand_start: Point { x: 500, y: 0 },
+            sand_start: Point::new(500, 0),
             lowest_rock_y: max_y,
-            min_x,
-            max_x,
+            floor: None,
         })
     }
 }
 
-impl Grid {
-    pub fn draw(&self) {
-        for y in 0..self.grid.len() {
-            print!("{:?}", y);
-            for i in (self.min_x)..(self.max_x) {
-                self.grid[y][i].draw()
-            }
-            println!();
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.grid.render(Tile::symbol))
+    }
+}
+
+impl Cave {
+    fn tile(&self, point: Point<i64>) -> Tile {
+        match self.floor {
+            Some(floor) if point.y == floor => Tile::Rock,
+            _ => *self.grid.get(point.x, point.y),
         }
     }
 
-    pub fn add_sand(&mut self) -> Option<Point> {
+    /// Where the sand below `point` moves next, if it can move at all.
+    fn fall(&self, point: Point<i64>) -> Option<Point<i64>> {
+        FALLS
+            .iter()
+            .map(|fall| point + *fall)
+            .find(|next| self.tile(*next) == Tile::Air)
+    }
+
+    pub fn add_sand(&mut self) -> Option<Point<i64>> {
         let mut current_position = self.sand_start;
 
-        loop {
-            if self.grid[current_position.y + 1][current_position.x] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x,
-                    y: current_position.y + 1,
-                };
-            } else if self.grid[current_position.y + 1][current_position.x - 1] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x - 1,
-                    y: current_position.y + 1,
-                };
-            } else if self.grid[current_position.y + 1][current_position.x + 1] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x + 1,
-                    y: current_position.y + 1,
-                };
-            } else {
-                break;
-            }
+        while let Some(next) = self.fall(current_position) {
+            current_position = next;
 
             if current_position.y >= self.lowest_rock_y {
                 return None;
             }
         }
 
-        self.grid[current_position.y][current_position.x] = Tile::Sand;
+        self.grid
+            .set(current_position.x, current_position.y, Tile::Sand);
 
-        return Some(current_position);
+        Some(current_position)
     }
 
     pub fn add_floor(&mut self) {
-        let place_floor_at = self.lowest_rock_y + 2;
-        for x in 0..self.grid[0].len() {
-            self.grid[place_floor_at][x] = Tile::Rock;
-        }
+        self.floor = Some(self.lowest_rock_y + 2);
     }
 
-    pub fn add_sand_until_blocked(&mut self) -> Option<Point> {
+    pub fn add_sand_until_blocked(&mut self) -> Option<Point<i64>> {
         let mut current_position = self.sand_start;
 
-        loop {
-            if self.grid[current_position.y + 1][current_position.x] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x,
-                    y: current_position.y + 1,
-                };
-            } else if self.grid[current_position.y + 1][current_position.x - 1] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x - 1,
-                    y: current_position.y + 1,
-                };
-            } else if self.grid[current_position.y + 1][current_position.x + 1] == Tile::Air {
-                current_position = Point {
-                    x: current_position.x + 1,
-                    y: current_position.y + 1,
-                };
-            } else {
-                if current_position.y == self.sand_start.y
-                    && current_position.x == self.sand_start.x
-                {
-                    return None;
-                }
+        while let Some(next) = self.fall(current_position) {
+            current_position = next;
+        }
 
-                break;
-            }
+        if current_position == self.sand_start {
+            return None;
         }
 
-        self.grid[current_position.y][current_position.x] = Tile::Sand;
+        self.grid
+            .set(current_position.x, current_position.y, Tile::Sand);
 
-        return Some(current_position);
+        Some(current_position)
     }
 }
 
-pub fn part_one() {
-    let mut grid = INPUT.parse::<Grid>().unwrap();
-    grid.draw();
+pub struct Day14;
 
-    let mut sand_count = 0;
+impl Solution for Day14 {
+    type Parsed = Cave;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        input.parse()
+    }
 
-    loop {
-        match grid.add_sand() {
-            None => break,
-            Some(_) => sand_count += 1,
+    fn part_one(cave: &Self::Parsed) -> Answer {
+        let mut cave = cave.clone();
+        crate::debug!("{cave}");
+
+        let mut sand_count = 0;
+
+        loop {
+            match cave.add_sand() {
+                None => break,
+                Some(_) => sand_count += 1,
+            }
         }
+        crate::debug!("{cave}");
+
+        sand_count.into()
     }
-    grid.draw();
 
-    println!("Count {:?}", sand_count);
+    fn part_two(cave: &Self::Parsed) -> Answer {
+        let mut cave = cave.clone();
+        cave.add_floor();
+
+        let mut sand_count = 0;
+
+        loop {
+            sand_count += 1;
+            if cave.add_sand_until_blocked().is_none() {
+                break;
+            }
+        }
+
+        sand_count.into()
+    }
 }
 
-pub fn part_two() {
-    let mut grid = INPUT.parse::<Grid>().unwrap();
-    grid.add_floor();
+#[cfg(test)]
+mod tests {
+    use super::Day14;
+    use crate::solution::{Answer, Part, Solution};
 
-    let mut sand_count = 0;
+    static INPUT_TEST: &str = include_str!("input-test.txt");
 
-    loop {
-        sand_count += 1;
-        match grid.add_sand_until_blocked() {
-            None => break,
-            Some(_) => {}
-        }
+    #[test]
+    fn test_part_one_test_data() {
+        assert_eq!(Day14::solve(INPUT_TEST, Part::One), Ok(Answer::from(24)))
     }
 
-    println!("Count {:?}", sand_count);
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(Day14::solve(INPUT_TEST, Part::Two), Ok(Answer::from(93)))
+    }
+
+    #[test]
+    fn test_diagonal_path() {
+        let err = Day14::solve("498,4 -> 498,6\n498,4 -> 500,5", Part::One).unwrap_err();
+
+        let position = err.position.unwrap();
+        assert_eq!((position.line, position.column), (2, 10));
+        assert_eq!(
+            err.message,
+            "the path from (498, 4) to (500, 5) is not straight"
+        );
+    }
 }
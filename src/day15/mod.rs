@@ -1,115 +1,189 @@
-use std::fmt::Debug;
-use std::{num::ParseIntError, str::FromStr};
-
-static INPUT: &'static str = include_str!("input-test.txt");
+use std::collections::HashSet;
+
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        geom::Point,
+        interval::{Interval, IntervalSet},
+        parse::{self, Parser},
+        Error,
+    },
+};
+
+/// The row part one asks about in the real puzzle, the sample says so in a header.
+const ROW: i64 = 2_000_000;
+/// The largest coordinate of the distress beacon in the real puzzle.
+const SIZE: i64 = 4_000_000;
+
+/// `x=2, y=-18`
+fn point(p: &mut Parser) -> Result<Point<i64>, Error> {
+    p.literal("x=")?;
+    let x = p.signed()?;
+    p.literal(", y=")?;
+    let y = p.signed()?;
+
+    Ok(Point::new(x, y))
+}
 
 #[derive(Debug)]
-struct Point<T: FromStr> {
-    x: T,
-    y: T,
+pub struct Sensor {
+    position: Point<i64>,
+    beacon: Point<i64>,
 }
 
-impl<T: FromStr> FromStr for Point<T> {
-    type Err = std::fmt::Error;
+impl Sensor {
+    fn parse(p: &mut Parser) -> Result<Self, Error> {
+        p.literal("Sensor at")?;
+        let position = point(p)?;
+        p.literal(": closest beacon is at")?;
+        let beacon = point(p)?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').unwrap();
-        let x = x.split_once('=').unwrap().1.parse::<T>();
-        let y = y.split_once('=').unwrap().1.parse::<T>();
+        Ok(Sensor { position, beacon })
+    }
 
-        match (x, y) {
-            (Ok(x), Ok(y)) => Ok(Point { x, y }),
-            (Err(_), Err(_)) => Err(std::fmt::Error),
-            (Err(_), Ok(_)) => Err(std::fmt::Error),
-            (Ok(_), Err(_)) => Err(std::fmt::Error),
-        }
+    /// No other beacon can be this close or closer to the sensor.
+    fn radius(&self) -> i64 {
+        self.position.manhattan(self.beacon)
     }
-}
 
-#[derive(Debug)]
-struct Sensor<T: FromStr> {
-    position: Point<T>,
-    beacon: Point<T>,
-}
+    /// The positions on row `y` that are within the radius, if any are.
+    fn coverage(&self, y: i64) -> Option<Interval<i64>> {
+        let reach = self.radius() - (self.position.y - y).abs();
 
-impl<T: FromStr> FromStr for Sensor<T> {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (sensor, beacon) = s.split_once(':').unwrap();
-        let sensor = sensor
-            .split_once("at")
-            .unwrap()
-            .1
-            .parse::<Point<T>>()
-            .unwrap();
-        let beacon = beacon
-            .split_once("at")
-            .unwrap()
-            .1
-            .parse::<Point<T>>()
-            .unwrap();
-
-        Ok(Sensor {
-            position: sensor,
-            beacon,
-        })
+        (reach >= 0).then(|| Interval::inclusive(self.position.x - reach, self.position.x + reach))
     }
 }
 
-fn parse_sensors(input: &str) -> Vec<Sensor<usize>> {
-    let sensors: Vec<_> = input
-        .lines()
-        .map(|line| line.parse::<Sensor<i32>>().unwrap())
+fn coverage(sensors: &[Sensor], y: i64) -> IntervalSet<i64> {
+    sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage(y))
+        .collect()
+}
+
+/// The positions on row `y` where there cannot be a beacon, known beacons excluded.
+fn excluded_positions(sensors: &[Sensor], y: i64) -> i64 {
+    let covered = coverage(sensors, y);
+
+    let beacons: HashSet<_> = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.y == y && covered.contains(beacon.x))
         .collect();
 
-    let mut max_x = i32::MIN;
-    let mut min_x = i32::MAX;
-
-    let mut max_y = i32::MIN;
-    let mut min_y = i32::MAX;
-
-    for sensor in &sensors {
-        println!("{:?}", sensor);
-        if sensor.position.x > max_x {
-            max_x = sensor.position.x;
-        } else if sensor.position.x < min_x {
-            min_x = sensor.position.x;
-        }
-        if sensor.beacon.x > max_x {
-            max_x = sensor.beacon.x;
-        } else if sensor.beacon.x < min_x {
-            min_x = sensor.beacon.x;
-        }
-
-        if sensor.position.y > max_y {
-            max_y = sensor.position.y;
-        } else if sensor.position.y < min_y {
-            min_y = sensor.position.y;
-        }
-        if sensor.beacon.y > max_y {
-            max_y = sensor.beacon.y;
-        } else if sensor.beacon.y < min_y {
-            min_y = sensor.beacon.y;
-        }
+    covered.len() - beacons.len() as i64
+}
+
+/**
+ * Finds the only position from `0` to `size` on both axes that no sensor covers. Every row
+ * is a handful of intervals, so checking all of them is fast enough.
+ */
+fn find_distress_beacon(sensors: &[Sensor], size: i64) -> Option<Point<i64>> {
+    let area = Interval::inclusive(0, size);
+
+    (0..=size).find_map(|y| {
+        let gaps = coverage(sensors, y).gaps(area);
+        gaps.intervals().first().map(|gap| Point::new(gap.start, y))
+    })
+}
+
+fn tuning_frequency(beacon: Point<i64>) -> i64 {
+    beacon.x * 4_000_000 + beacon.y
+}
+
+pub struct Report {
+    sensors: Vec<Sensor>,
+    /// The row part one counts the excluded positions on.
+    row: i64,
+    /// The largest coordinate of the distress beacon.
+    size: i64,
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Parsed = Report;
+
+    /**
+     * The row and the size of the area are only part of the puzzle text, not of the input.
+     * Inputs for other values start with a header like `Row y=10, search up to 20`.
+     */
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let mut p = Parser::new(input);
+
+        let (row, size) = match p.try_literal("Row y=") {
+            true => {
+                let row = p.signed()?;
+                p.literal(", search up to")?;
+                (row, p.unsigned()?)
+            }
+            false => (ROW, SIZE),
+        };
+        let sensors = parse::lines(p.rest(), Sensor::parse)?;
+
+        Ok(Report { sensors, row, size })
     }
 
-    // Adjust negative position to indexable ones
-    sensors
-        .iter()
-        .map(|sensor| Sensor::<usize> {
-            position: Point::<usize> {
-                x: (sensor.position.x + (min_x * -1)) as usize,
-                y: (sensor.position.y + (min_y * -1)) as usize,
-            },
-            beacon: Point::<usize> {
-                x: (sensor.beacon.x + (min_x * -1)) as usize,
-                y: (sensor.beacon.y + (min_y * -1)) as usize,
-            },
-        })
-        .collect()
+    fn part_one(report: &Self::Parsed) -> Answer {
+        excluded_positions(&report.sensors, report.row).into()
+    }
+
+    fn part_two(report: &Self::Parsed) -> Answer {
+        find_distress_beacon(&report.sensors, report.size)
+            .map(tuning_frequency)
+            .ok_or_else(|| Error::new("the sensors cover the whole area"))
+            .into()
+    }
 }
 
-pub fn part_one() {
-    let sensors = parse_sensors(INPUT);
+#[cfg(test)]
+mod tests {
+    use super::{excluded_positions, find_distress_beacon, tuning_frequency, Day15};
+    use crate::{
+        solution::{Answer, Part, Solution},
+        util::geom::Point,
+    };
+
+    static INPUT_TEST: &str = include_str!("input-test.txt");
+
+    #[test]
+    fn test_part_one_test_data() {
+        let report = Day15::parse(INPUT_TEST).unwrap();
+
+        assert_eq!(excluded_positions(&report.sensors, 10), 26);
+        assert_eq!(Day15::solve(INPUT_TEST, Part::One), Ok(Answer::from(26u64)));
+    }
+
+    #[test]
+    fn test_part_two_test_data() {
+        let report = Day15::parse(INPUT_TEST).unwrap();
+        let beacon = find_distress_beacon(&report.sensors, 20);
+
+        assert_eq!(beacon, Some(Point::new(14, 11)));
+        assert_eq!(tuning_frequency(beacon.unwrap()), 56000011);
+        assert_eq!(
+            Day15::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(56000011u64))
+        );
+    }
+
+    #[test]
+    fn test_without_header() {
+        let input = INPUT_TEST.split_once('\n').unwrap().1;
+        let report = Day15::parse(input).unwrap();
+
+        assert_eq!((report.row, report.size), (2_000_000, 4_000_000));
+        assert_eq!(report.sensors.len(), 14);
+    }
+
+    #[test]
+    fn test_everything_covered() {
+        let input = "Row y=10, search up to 20\n\
+                     Sensor at x=10, y=10: closest beacon is at x=10, y=30";
+
+        assert_eq!(
+            Day15::solve(input, Part::Two).unwrap().to_string(),
+            "error: the sensors cover the whole area"
+        );
+    }
 }
@@ -1,16 +1,21 @@
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet},
-    num::ParseIntError,
     rc::Rc,
-    str::FromStr,
     time::Instant,
 };
 
-static INPUT: &str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        graph,
+        parse::{self, Parser},
+        Error,
+    },
+};
 
-#[derive(Debug)]
-struct Valve {
+#[derive(Debug, Clone)]
+pub struct Valve {
     pub name: String,
     pub flow_rate: usize,
     pub tunnels: Vec<String>,
@@ -18,26 +23,33 @@ struct Valve {
     pub distance_to: HashMap<String, usize>,
 }
 
-impl FromStr for Valve {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (definition, tunnels) = s.split_once(';').unwrap();
-        let (pretext, flow_rate) = definition.split_once('=').unwrap();
-
-        let (_, tunnels) = tunnels.split_once("valve").unwrap();
-        // Skip leading 's' of 'valves' if there are multiple
-        let tunnels: Vec<_> = tunnels[1..]
-            .split(',')
-            .map(|tunnel| String::from(tunnel.trim()))
-            .collect();
-
-        Ok(Valve {
-            name: pretext[6..8].to_string(),
-            flow_rate: flow_rate.parse::<usize>().unwrap(),
-            tunnels,
+/// The valve both parts start at.
+const START: &str = "AA";
+/// Every subset of the valves with a flow rate gets an entry, so there cannot be many.
+const MAX_FLOW_VALVES: usize = 24;
+
+impl Valve {
+    /// `Valve AA has flow rate=0; tunnels lead to valves DD, II, BB`, with the tunnels as read.
+    fn parse<'a>(p: &mut Parser<'a>) -> Result<(Self, Vec<&'a str>), Error> {
+        p.literal("Valve")?;
+        let name = p.word()?;
+        p.literal("has flow rate=")?;
+        let flow_rate = p.unsigned()?;
+        p.literal(";")?;
+        p.one_of(&[
+            ("tunnels lead to valves", ()),
+            ("tunnel leads to valve", ()),
+        ])?;
+        let tunnels = p.separated(",", Parser::word)?;
+
+        let valve = Valve {
+            name: name.to_string(),
+            flow_rate,
+            tunnels: tunnels.iter().copied().map(String::from).collect(),
             distance_to: HashMap::new(),
-        })
+        };
+
+        Ok((valve, tunnels))
     }
 }
 
@@ -45,129 +57,94 @@ impl FromStr for Valve {
 struct Path {
     pub current_valve: Rc<RefCell<Valve>>,
     pub accumulated_flow: usize,
-    pub valves_to_visit: Vec<Rc<RefCell<Valve>>>,
+    /// Bit `i` is set once the `i`th valve with a flow rate is open.
+    pub opened: usize,
     pub remaining_minutes: usize,
-    pub previous_valves: Vec<String>,
-}
-
-struct Iteration {
-    tunnels: Vec<String>,
-    current_distance: usize,
 }
 
 fn calculate_distances(
-    valves: &Vec<Rc<RefCell<Valve>>>,
+    valves: &[Rc<RefCell<Valve>>],
     valves_map: &HashMap<String, Rc<RefCell<Valve>>>,
 ) {
-    let to_find_for_all: HashSet<String> =
-        HashSet::from_iter(valves.iter().map(|valve| valve.borrow().name.clone()));
-
-    // TODO: Optimize by dijkstra?
-    for valve in valves {
-        let mut to_find = to_find_for_all.clone();
-        let mut current_valve = valve.borrow_mut();
-
-        to_find.remove(&current_valve.name);
-
-        // TODO: Maybe move inside function
-        let tunnels_to_follow: Vec<_> = current_valve.tunnels.to_vec();
-
-        // TODO: Maybe move inside function
-        for tunnel in &tunnels_to_follow {
-            to_find.remove(tunnel);
-            current_valve.distance_to.insert(tunnel.clone(), 1);
-        }
+    let names: Vec<String> = valves
+        .iter()
+        .map(|valve| valve.borrow().name.clone())
+        .collect();
 
-        let mut to_follow: Vec<Iteration> = vec![Iteration {
-            tunnels: tunnels_to_follow,
-            current_distance: 1,
-        }];
-
-        while let Some(iteration) = to_follow.pop() {
-            for j in 0..iteration.tunnels.len() {
-                let current = valves_map
-                    .get(iteration.tunnels[j].as_str())
-                    .unwrap()
-                    .borrow();
-
-                let tunnels_to_follow: Vec<_> = current
-                    .tunnels
-                    .iter()
-                    .filter(|tunnel| to_find.contains(*tunnel))
-                    .cloned()
-                    .collect();
-
-                for tunnel in &tunnels_to_follow {
-                    to_find.remove(tunnel);
-                    current_valve
-                        .distance_to
-                        .insert(tunnel.clone(), iteration.current_distance + 1);
-                }
-
-                to_follow.push(Iteration {
-                    tunnels: tunnels_to_follow,
-                    current_distance: iteration.current_distance + 1,
-                });
+    let distances = graph::all_pairs(names.iter().cloned(), |name| {
+        valves_map[name]
+            .borrow()
+            .tunnels
+            .iter()
+            .map(|tunnel| (tunnel.clone(), 1))
+            .collect::<Vec<_>>()
+    });
+
+    for (valve, name) in valves.iter().zip(&names) {
+        let mut valve = valve.borrow_mut();
+
+        for other in names.iter().filter(|other| *other != name) {
+            if let Some(distance) = distances.cost(name, other) {
+                valve.distance_to.insert(other.clone(), distance as usize);
             }
         }
     }
 }
 
-fn find_max_releasable_pressure(
+/**
+ * The most pressure that can be released by opening exactly the valves of a subset, for
+ * every subset of the valves with a flow rate. Bit `i` of the index stands for the `i`th of
+ * them.
+ */
+fn find_max_pressure_per_subset(
     start_valve: String,
     valves: &[Rc<RefCell<Valve>>],
     valves_map: &HashMap<String, Rc<RefCell<Valve>>>,
-) -> usize {
+    minutes: usize,
+) -> Vec<usize> {
     let relevant_valves: Vec<_> = valves
         .iter()
         .filter(|valve| valve.borrow().flow_rate > 0)
         .map(Rc::clone)
         .collect();
 
+    let mut max = vec![0; 1 << relevant_valves.len()];
+
     let mut paths: Vec<Path> = Vec::from([Path {
         current_valve: Rc::clone(valves_map.get(&start_valve).unwrap()),
-        valves_to_visit: relevant_valves,
+        opened: 0,
         accumulated_flow: 0,
-        remaining_minutes: 30,
-        previous_valves: vec![],
+        remaining_minutes: minutes,
     }]);
 
-    let mut max: usize = 0;
-
     while let Some(path) = paths.pop() {
+        max[path.opened] = max[path.opened].max(path.accumulated_flow);
+
         let current_valve = path.current_valve.borrow();
 
-        for (i, path_valve) in path.valves_to_visit.iter().enumerate() {
+        for (i, path_valve) in relevant_valves.iter().enumerate() {
+            if path.opened & (1 << i) != 0 {
+                continue;
+            }
+
             let valve = path_valve.borrow();
-            let steps_to_valve = *current_valve.distance_to.get(&valve.name).unwrap();
+            let Some(steps_to_valve) = current_valve.distance_to.get(&valve.name) else {
+                continue;
+            };
 
-            // It takes one more minute to open the valve.
+            // It takes one more minute to open the valve, which only helps if there is time
+            // left afterwards.
             let cost = steps_to_valve + 1;
-            let remaining_minutes = path.remaining_minutes.saturating_sub(cost);
-            let accumulated_flow = path.accumulated_flow + (valve.flow_rate * remaining_minutes);
-
-            if path.valves_to_visit.len() == 1 || remaining_minutes == 0 {
-                if accumulated_flow > max {
-                    max = accumulated_flow;
-                    // path.previous_valves.push(valve.name.clone());
-                    // println!("{:?}", path.previous_valves);
-                }
-
+            if cost >= path.remaining_minutes {
                 continue;
             }
-
-            let mut valves_to_visit = path.valves_to_visit.clone();
-            valves_to_visit.remove(i);
-
-            // let mut previous_valves = path.previous_valves.clone();
-            // previous_valves.push(current_valve.name.clone());
+            let remaining_minutes = path.remaining_minutes - cost;
 
             paths.push(Path {
                 current_valve: Rc::clone(path_valve),
+                opened: path.opened | (1 << i),
+                accumulated_flow: path.accumulated_flow + (valve.flow_rate * remaining_minutes),
                 remaining_minutes,
-                accumulated_flow,
-                valves_to_visit,
-                previous_valves: vec![],
             });
         }
     }
@@ -175,32 +152,161 @@ fn find_max_releasable_pressure(
     max
 }
 
-pub fn part_one() {
+fn max_pressure_per_subset(valves: &[Valve], minutes: usize) -> Vec<usize> {
     let now = Instant::now();
 
-    let valves: Vec<_> = INPUT
-        .lines()
-        .map(|line| line.parse::<Valve>().unwrap())
+    let valves: Vec<_> = valves
+        .iter()
+        .cloned()
         .map(|valve| Rc::new(RefCell::new(valve)))
         .collect();
 
-    let start_valve = String::from("AA");
-    // let start_valve = valves[0].borrow().name.clone();
+    let start_valve = String::from(START);
 
     let valves_map: HashMap<String, Rc<RefCell<Valve>>> = valves
         .iter()
         .map(|valve| (valve.borrow().name.clone(), Rc::clone(valve)))
         .collect();
 
-    println!("setup: {:.2?}", now.elapsed());
+    crate::debug!("setup: {:.2?}", now.elapsed());
 
     calculate_distances(&valves, &valves_map);
 
-    println!("Distances: {:.2?}", now.elapsed());
+    crate::debug!("Distances: {:.2?}", now.elapsed());
 
-    let max = find_max_releasable_pressure(start_valve, &valves, &valves_map);
+    find_max_pressure_per_subset(start_valve, &valves, &valves_map, minutes)
+}
+
+fn release_most_pressure(valves: &[Valve]) -> usize {
+    max_pressure_per_subset(valves, 30)
+        .into_iter()
+        .max()
+        .unwrap()
+}
 
-    // 1838 too low, 1879, 1880 too high
-    // => 1857 => What am i missing?
-    println!("{max}");
+/**
+ * You and the elephant open disjoint sets of valves, so the best of both is the best pair
+ * of a subset and any subset of the valves left over.
+ */
+fn release_most_pressure_with_elephant(valves: &[Valve]) -> usize {
+    let max = max_pressure_per_subset(valves, 26);
+
+    // The best of every subset and all of its own subsets, built up one valve at a time.
+    let mut best_within = max.clone();
+    for subset in 0..best_within.len() {
+        let mut valves = subset;
+        while valves != 0 {
+            let valve = valves & valves.wrapping_neg();
+            best_within[subset] = best_within[subset].max(best_within[subset ^ valve]);
+            valves ^= valve;
+        }
+    }
+
+    let all = max.len() - 1;
+    (0..max.len())
+        .map(|subset| max[subset] + best_within[all ^ subset])
+        .max()
+        .unwrap()
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = Vec<Valve>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let lines = parse::lines(input, Valve::parse)?;
+
+        let names: HashSet<&str> = lines.iter().map(|(valve, _)| valve.name.as_str()).collect();
+        if let Some(tunnel) = lines
+            .iter()
+            .flat_map(|(_, tunnels)| tunnels)
+            .find(|tunnel| !names.contains(*tunnel))
+        {
+            return Err(Error::at(tunnel, format!("there is no valve `{tunnel}`")));
+        }
+        if !names.contains(START) {
+            return Err(Error::new(format!("the valve `{START}` is missing")));
+        }
+
+        let valves: Vec<Valve> = lines.into_iter().map(|(valve, _)| valve).collect();
+        let flow_valves = valves.iter().filter(|valve| valve.flow_rate > 0).count();
+        if flow_valves > MAX_FLOW_VALVES {
+            return Err(Error::new(format!(
+                "{flow_valves} valves have a flow rate, at most {MAX_FLOW_VALVES} are supported"
+            )));
+        }
+
+        Ok(valves)
+    }
+
+    fn part_one(valves: &Self::Parsed) -> Answer {
+        release_most_pressure(valves).into()
+    }
+
+    fn part_two(valves: &Self::Parsed) -> Answer {
+        release_most_pressure_with_elephant(valves).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day16;
+    use crate::solution::{Answer, Part, Solution};
+
+    static INPUT_TEST: &str = include_str!("input-test.txt");
+    static INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn test_unknown_valves() {
+        let err = Day16::solve(
+            "Valve AA has flow rate=0; tunnel leads to valve BB",
+            Part::One,
+        )
+        .unwrap_err();
+        assert_eq!(err.message, "there is no valve `BB`");
+        assert_eq!(err.position.unwrap().column, 49);
+
+        let err = Day16::solve(
+            "Valve BB has flow rate=1; tunnel leads to valve BB",
+            Part::One,
+        )
+        .unwrap_err();
+        assert_eq!(err.message, "the valve `AA` is missing");
+    }
+
+    #[test]
+    fn test_unreachable_valve() {
+        let input = "Valve AA has flow rate=0; tunnel leads to valve AA\n\
+                     Valve BB has flow rate=5; tunnel leads to valve BB";
+
+        assert_eq!(Day16::solve(input, Part::One), Ok(Answer::from(0u64)));
+    }
+
+    #[test]
+    fn test_part_one_test_data() {
+        assert_eq!(
+            Day16::solve(INPUT_TEST, Part::One),
+            Ok(Answer::from(1651u64))
+        )
+    }
+
+    /// Used to be 1838, when the distances missed shorter paths through visited valves.
+    #[test]
+    fn test_part_one() {
+        assert_eq!(Day16::solve(INPUT, Part::One), Ok(Answer::from(1857u64)))
+    }
+
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(
+            Day16::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(1707u64))
+        )
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(Day16::solve(INPUT, Part::Two), Ok(Answer::from(2536u64)))
+    }
 }
@@ -1,6 +1,13 @@
-use std::{iter::Cycle, slice::Iter, str::FromStr, string::ParseError};
+use std::{collections::HashMap, str::FromStr, string::ParseError};
+
+use crate::{
+    solution::{Answer, Part, Solution, Variant},
+    util::Error,
+};
+
+#[path = "mod_naive.rs"]
+mod naive;
 
-static INPUT: &str = include_str!("input-test.txt");
 static INPUT_ROCKS: &str = include_str!("input-rocks.txt");
 
 const CHAMBER_WIDTH: usize = 7;
@@ -82,14 +89,49 @@ impl Rock {
 }
 
 #[derive(Debug)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
+/**
+ * The tower grows the same way over and over once a state repeats, see
+ * [`Chamber::find_cycle`].
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// The rocks that fall before the repetition starts.
+    pub offset: usize,
+    /// The rocks per repetition.
+    pub length: usize,
+    /// The height of the tower after each of the first `offset + length` rocks, and the one
+    /// after.
+    heights: Vec<usize>,
+}
+
+impl Cycle {
+    /// The height the tower gains per repetition.
+    pub fn height(&self) -> usize {
+        self.heights[self.offset + self.length] - self.heights[self.offset]
+    }
+
+    pub fn tower_height(&self, rocks: usize) -> usize {
+        if rocks < self.heights.len() {
+            return self.heights[rocks];
+        }
+
+        let repetitions = (rocks - self.offset) / self.length;
+        let remainder = (rocks - self.offset) % self.length;
+
+        self.heights[self.offset + remainder] + repetitions * self.height()
+    }
+}
+
 struct Chamber<'a> {
     values: Vec<u8>,
-    stream: Cycle<Iter<'a, Direction>>,
+    stream: &'a [Direction],
+    /// The index of the next jet in `stream`.
+    jet: usize,
     pub max_height: usize,
 }
 
@@ -103,12 +145,16 @@ impl<'a> Chamber<'a> {
         Chamber {
             values,
             max_height: 1,
-            stream: stream.iter().cycle(),
+            stream,
+            jet: 0,
         }
     }
 
     pub fn move_rock_horizontal(&mut self, rock: &mut Rock) {
-        match self.stream.next().unwrap() {
+        let direction = &self.stream[self.jet];
+        self.jet = (self.jet + 1) % self.stream.len();
+
+        match direction {
             Direction::Left => {
                 if rock.x == 0 {
                     return;
@@ -195,34 +241,168 @@ impl<'a> Chamber<'a> {
 
         self.add_rock_to_structure(&rock);
     }
+
+    /**
+     * How far below the top each column is blocked. Falling rocks can only reach further
+     * down through a gap that is deeper than any of these, which the rock shapes make
+     * unlikely enough that the profile stands in for the whole tower.
+     */
+    fn profile(&self) -> [usize; CHAMBER_WIDTH] {
+        let top = self.max_height - 1;
+
+        std::array::from_fn(|x| {
+            // The floor is full, so every column is blocked at some point.
+            let y = (0..=top)
+                .rev()
+                .find(|y| self.values[*y] & (1 << x) != 0)
+                .unwrap();
+            top - y
+        })
+    }
+
+    /**
+     * Drops rocks until the next rock, the next jet and the shape of the top of the tower
+     * are the same as they were before. From there on, the tower grows in repetitions.
+     */
+    pub fn find_cycle(&mut self, blueprints: &[Blueprint]) -> Cycle {
+        let mut seen = HashMap::new();
+        let mut heights = vec![self.max_height - 1];
+
+        for rocks in 0.. {
+            let rock = rocks % blueprints.len();
+            let state = (rock, self.jet, self.profile());
+
+            if let Some(&offset) = seen.get(&state) {
+                return Cycle {
+                    offset,
+                    length: rocks - offset,
+                    heights,
+                };
+            }
+            seen.insert(state, rocks);
+
+            self.add_rock(&blueprints[rock]);
+            heights.push(self.max_height - 1);
+        }
+
+        unreachable!("there are only so many states")
+    }
 }
 
-// Naive: 8ms
-// Point List: 5ms
-// Bitmask: 3.4ms
-// Bitmask OO: 3.8ms
-// TODO: Matrix? from_le_bytes
-pub fn part_one() {
-    let blueprints = INPUT_ROCKS
+fn blueprints() -> Vec<Blueprint> {
+    INPUT_ROCKS
         .split("\n\n")
         .map(|block| block.parse::<Blueprint>().unwrap())
-        .collect::<Vec<_>>();
-    let mut blueprints = blueprints.iter().cycle();
+        .collect()
+}
 
-    let stream = INPUT
-        .chars()
-        .map(|c| match c {
-            '<' => Direction::Left,
-            _ => Direction::Right,
-        })
-        .collect::<Vec<_>>();
+// Rows are bitmasks, which beat the naive matrix and a list of points.
+// TODO: Matrix? from_le_bytes
+fn tower_height(stream: &[Direction], rocks: usize) -> usize {
+    let blueprints = blueprints();
+    let mut blueprints = blueprints.iter().cycle();
 
-    let mut chamber = Chamber::new(&stream);
+    let mut chamber = Chamber::new(stream);
 
-    for _ in 0..2022 {
+    for _ in 0..rocks {
         chamber.add_rock(blueprints.next().unwrap());
     }
 
-    // 3068
-    println!("Height: {:?}", chamber.max_height - 1)
+    chamber.max_height - 1
+}
+
+fn find_cycle(stream: &[Direction]) -> Cycle {
+    let cycle = Chamber::new(stream).find_cycle(&blueprints());
+    crate::debug!(
+        "cycle of {} rocks after {}, {} high",
+        cycle.length,
+        cycle.offset,
+        cycle.height()
+    );
+
+    cycle
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    /// The jet pattern.
+    type Parsed = Vec<Direction>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(Error::new("there are no jets to push the rocks"));
+        }
+
+        input
+            .char_indices()
+            .map(|(i, c)| match c {
+                '<' => Ok(Direction::Left),
+                '>' => Ok(Direction::Right),
+                _ => Err(Error::at(&input[i..], format!("unexpected jet `{c}`"))),
+            })
+            .collect()
+    }
+
+    fn part_one(stream: &Self::Parsed) -> Answer {
+        tower_height(stream, 2022).into()
+    }
+
+    fn part_two(stream: &Self::Parsed) -> Answer {
+        find_cycle(stream).tower_height(1_000_000_000_000).into()
+    }
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            name: "naive",
+            part: Part::One,
+            solve: |stream| naive::tower_height(stream, 2022).into(),
+        },
+        Variant {
+            name: "cycle",
+            part: Part::One,
+            solve: |stream| find_cycle(stream).tower_height(2022).into(),
+        },
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, tower_height, Day17};
+    use crate::solution::{Answer, Part, Solution};
+
+    static INPUT_TEST: &str = include_str!("input-test.txt");
+    static INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn test_no_jets() {
+        for input in ["", " \n "] {
+            let err = Day17::parse(input).err().unwrap();
+            assert_eq!(err.message, "there are no jets to push the rocks");
+        }
+    }
+
+    #[test]
+    fn test_cycle_test_data() {
+        let stream = Day17::parse(INPUT_TEST).unwrap();
+        let cycle = find_cycle(&stream);
+
+        assert_eq!(cycle.length, 35);
+        assert_eq!(cycle.height(), 53);
+        assert_eq!(
+            Day17::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(1514285714288u64))
+        );
+    }
+
+    #[test]
+    fn test_cycle_matches_simulation() {
+        let stream = Day17::parse(INPUT).unwrap();
+        let cycle = find_cycle(&stream);
+
+        for rocks in [0, 1, 2022, cycle.offset + 3 * cycle.length + 7, 20_000] {
+            assert_eq!(cycle.tower_height(rocks), tower_height(&stream, rocks));
+        }
+    }
 }
@@ -1,7 +1,6 @@
 use std::{str::FromStr, string::ParseError};
 
-static INPUT: &str = include_str!("input.txt");
-static INPUT_ROCKS: &str = include_str!("input-rocks.txt");
+use super::{Direction, INPUT_ROCKS};
 
 #[derive(Clone, Debug)]
 struct Rock {
@@ -37,12 +36,6 @@ impl FromStr for Rock {
     }
 }
 
-#[derive(Debug)]
-enum Direction {
-    Left,
-    Right,
-}
-
 struct Chamber {
     pub points: Vec<Vec<bool>>,
     pub max_height: usize,
@@ -66,7 +59,7 @@ impl Chamber {
     }
 }
 
-pub fn part_one() {
+pub fn tower_height(stream: &[Direction], rock_amount: usize) -> usize {
     let mut chamber = Chamber::new();
 
     let rocks = INPUT_ROCKS
@@ -75,13 +68,6 @@ pub fn part_one() {
         .collect::<Vec<_>>();
     let mut rocks = rocks.iter().cycle();
 
-    let stream = INPUT
-        .chars()
-        .map(|c| match c {
-            '<' => Direction::Left,
-            _ => Direction::Right,
-        })
-        .collect::<Vec<_>>();
     let mut stream = stream.iter().cycle();
 
     let mut rock_count = 0;
@@ -93,17 +79,13 @@ pub fn part_one() {
         let rock = rocks.next().unwrap();
         // Anchor is lower left pixel
         let mut rock_y = chamber.max_height + ROCK_START_DIFF;
-        let mut rock_x = 2;
+        let mut rock_x: usize = 2;
 
         loop {
             // As long as we are above the highest rock, no need for sophisticated collision checks.
             if rock_y - 1 > chamber.max_height {
                 match stream.next().unwrap() {
-                    Direction::Left => {
-                        if rock_x > 0 {
-                            rock_x -= 1
-                        }
-                    }
+                    Direction::Left => rock_x = rock_x.saturating_sub(1),
                     Direction::Right => {
                         if rock_x + rock.width < CHAMBER_WIDTH {
                             rock_x += 1
@@ -187,10 +169,10 @@ pub fn part_one() {
             }
         }
 
-        if rock_count == 2022 {
+        if rock_count == rock_amount {
             break;
         }
     }
 
-    println!("Height: {:?}", chamber.max_height - 1)
+    chamber.max_height - 1
 }
@@ -1,155 +1,221 @@
-use std::{cell::RefCell, collections::HashMap, str::FromStr, string::ParseError, time::Instant};
-
-static INPUT: &str = include_str!("input.txt");
-
-#[derive(Debug, PartialEq)]
-struct Coordinate {
-    pub x: usize,
-    pub y: usize,
-    pub z: usize,
-    pub value: usize,
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        error::{self, Error},
+        geom::{Point3, Vector3},
+    },
+};
+
+pub struct Droplet {
+    cubes: HashSet<Point3<i64>>,
 }
 
-impl FromStr for Coordinate {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(',').map(|value| value.parse::<usize>().unwrap());
-
-        let x = parts.next().unwrap();
-        let y = parts.next().unwrap();
-        let z = parts.next().unwrap();
-
-        let value = x * 100 + y * 10 + z;
+/// Cells that are found to be connected, merged into groups as the connections come up.
+struct Groups {
+    parent: Vec<usize>,
+}
 
-        Ok(Coordinate { y, x, z, value })
+impl Groups {
+    fn new(len: usize) -> Self {
+        Groups {
+            parent: (0..len).collect(),
+        }
     }
-}
 
-impl Coordinate {
-    pub fn new(x: usize, y: usize, z: usize) -> Self {
-        Coordinate {
-            x,
-            y,
-            z,
-            value: x * 100 + y * 10 + z,
+    fn find(&mut self, mut cell: usize) -> usize {
+        while self.parent[cell] != cell {
+            self.parent[cell] = self.parent[self.parent[cell]];
+            cell = self.parent[cell];
         }
+        cell
     }
-}
 
-#[derive(Debug)]
-struct Face {
-    pub coordinates: [Coordinate; 4],
-    // Used to categorize the face for indexing.
-    pub value: usize,
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
 }
 
-impl Face {
-    pub fn new(coordinates: [Coordinate; 4]) -> Self {
-        Face {
-            value: coordinates.iter().map(|c| c.value).sum(),
-            coordinates,
+impl Droplet {
+    pub fn new(cubes: HashSet<Point3<i64>>) -> Result<Self, Error> {
+        if cubes.is_empty() {
+            return Err(Error::new("the droplet has no cubes"));
         }
+
+        Ok(Droplet { cubes })
+    }
+
+    /// The faces of all cubes that do not touch another cube.
+    pub fn surface_area(&self) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|cube| cube.neighbours())
+            .filter(|neighbour| !self.cubes.contains(neighbour))
+            .count()
     }
-}
 
-impl PartialEq for Face {
-    fn eq(&self, other: &Self) -> bool {
-        for i in 0..self.coordinates.len() {
-            if self.coordinates[i] != other.coordinates[i] {
-                return false;
+    /**
+     * Like [`Droplet::surface_area`], without the faces towards air pockets inside.
+     *
+     * Only the air that touches a cube, even at an edge or a corner, is looked at, so the
+     * cubes can be any distance apart. Air cells next to each other are connected, and so
+     * is every one of them to the first cell after the lava in front of it along x, or to
+     * the outside if there is none. That covers every way through the air in between.
+     */
+    pub fn exterior_surface_area(&self) -> usize {
+        // Index 0 is the outside, far away from every cube.
+        let mut skin: HashMap<Point3<i64>, usize> = HashMap::new();
+        for cube in &self.cubes {
+            for x in -1..=1 {
+                for y in -1..=1 {
+                    for z in -1..=1 {
+                        let cell = *cube + Vector3::new(x, y, z);
+                        if !self.cubes.contains(&cell) {
+                            let index = skin.len() + 1;
+                            skin.entry(cell).or_insert(index);
+                        }
+                    }
+                }
             }
         }
-        true
-    }
-}
 
-struct Cube {
-    pub faces: [Face; 6],
-}
+        // The lava along every line in x, sorted.
+        let mut lines: HashMap<(i64, i64), Vec<i64>> = HashMap::new();
+        for cube in &self.cubes {
+            lines.entry((cube.y, cube.z)).or_default().push(cube.x);
+        }
+        for line in lines.values_mut() {
+            line.sort_unstable();
+        }
+
+        let mut groups = Groups::new(skin.len() + 1);
+        for (cell, index) in &skin {
+            for neighbour in cell.neighbours() {
+                if let Some(other) = skin.get(&neighbour) {
+                    groups.join(*index, *other);
+                }
+            }
+
+            let behind = lines
+                .get(&(cell.y, cell.z))
+                .and_then(|lava| lava[..lava.partition_point(|x| *x < cell.x)].last());
+            let end = match behind {
+                Some(x) => skin[&Point3::new(x + 1, cell.y, cell.z)],
+                None => 0,
+            };
+            groups.join(*index, end);
+        }
 
-impl FromStr for Cube {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let position = s.parse::<Coordinate>().unwrap();
-
-        let faces = [
-            Face::new([
-                Coordinate::new(position.x, position.y, position.z),
-                Coordinate::new(position.x, position.y, position.z + 1),
-                Coordinate::new(position.x, position.y + 1, position.z),
-                Coordinate::new(position.x, position.y + 1, position.z + 1),
-            ]),
-            Face::new([
-                Coordinate::new(position.x, position.y, position.z),
-                Coordinate::new(position.x, position.y, position.z + 1),
-                Coordinate::new(position.x + 1, position.y, position.z),
-                Coordinate::new(position.x + 1, position.y, position.z + 1),
-            ]),
-            Face::new([
-                Coordinate::new(position.x, position.y, position.z),
-                Coordinate::new(position.x, position.y + 1, position.z),
-                Coordinate::new(position.x + 1, position.y, position.z),
-                Coordinate::new(position.x + 1, position.y + 1, position.z),
-            ]),
-            Face::new([
-                Coordinate::new(position.x, position.y + 1, position.z),
-                Coordinate::new(position.x, position.y + 1, position.z + 1),
-                Coordinate::new(position.x + 1, position.y + 1, position.z),
-                Coordinate::new(position.x + 1, position.y + 1, position.z + 1),
-            ]),
-            Face::new([
-                Coordinate::new(position.x + 1, position.y, position.z),
-                Coordinate::new(position.x + 1, position.y, position.z + 1),
-                Coordinate::new(position.x + 1, position.y + 1, position.z),
-                Coordinate::new(position.x + 1, position.y + 1, position.z + 1),
-            ]),
-            Face::new([
-                Coordinate::new(position.x, position.y, position.z + 1),
-                Coordinate::new(position.x, position.y + 1, position.z + 1),
-                Coordinate::new(position.x + 1, position.y, position.z + 1),
-                Coordinate::new(position.x + 1, position.y + 1, position.z + 1),
-            ]),
-        ];
-
-        Ok(Cube { faces })
+        let outside = groups.find(0);
+        self.cubes
+            .iter()
+            .flat_map(|cube| cube.neighbours())
+            .filter(|neighbour| {
+                skin.get(neighbour)
+                    .is_some_and(|index| groups.find(*index) == outside)
+            })
+            .count()
     }
 }
 
-// 11ms/3.7ms
-pub fn part_one() {
-    let now = Instant::now();
+pub struct Day18;
 
-    let cubes: Vec<_> = INPUT
-        .lines()
-        .map(|line| line.parse::<Cube>().unwrap())
-        .collect();
+impl Solution for Day18 {
+    type Parsed = Droplet;
 
-    let max_faces = cubes.len() * 6;
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let cubes: Vec<Point3<i32>> = error::lines(input)?;
 
-    let mut existing_faces: HashMap<usize, RefCell<Vec<&Face>>> = HashMap::with_capacity(max_faces);
+        // Wide enough that the neighbours of any cube exist.
+        Droplet::new(
+            cubes
+                .into_iter()
+                .map(|cube| Point3::new(cube.x.into(), cube.y.into(), cube.z.into()))
+                .collect(),
+        )
+    }
 
-    println!("setup: {:.2?}", now.elapsed());
-    println!("Faces: {max_faces}");
+    fn part_one(droplet: &Self::Parsed) -> Answer {
+        droplet.surface_area().into()
+    }
 
-    let mut free_faces = max_faces;
+    fn part_two(droplet: &Self::Parsed) -> Answer {
+        droplet.exterior_surface_area().into()
+    }
+}
 
-    for face in cubes.iter().flat_map(|c| &c.faces) {
-        match existing_faces.get(&face.value) {
-            None => {
-                existing_faces.insert(face.value, RefCell::new(vec![face]));
-            }
-            Some(face_collection) => {
-                let mut face_collection = face_collection.borrow_mut();
+#[cfg(test)]
+mod tests {
+    use super::Day18;
+    use crate::solution::{Answer, Part, Solution};
+
+    static INPUT_TEST: &str = include_str!("input-test-2.txt");
+    static INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn test_any_coordinates() {
+        // Both used to share a face index, and neither is next to the other.
+        assert_eq!(
+            Day18::solve("1,0,0\n0,10,0", Part::One),
+            Ok(Answer::from(12u64))
+        );
+        assert_eq!(
+            Day18::solve("-5,100,7\n-5,101,7", Part::One),
+            Ok(Answer::from(10u64))
+        );
+        assert!(Day18::solve("", Part::One).is_err());
+    }
 
-                match face_collection.iter().find(|other| ***other == *face) {
-                    Some(_) => free_faces -= 2,
-                    None => face_collection.push(face),
+    #[test]
+    fn test_far_apart() {
+        for input in [
+            "0,0,0\n0,0,100",
+            "0,0,0\n2000,2000,2000",
+            "-2147483648,0,0\n2147483647,0,0",
+        ] {
+            assert_eq!(Day18::solve(input, Part::One), Ok(Answer::from(12u64)));
+            assert_eq!(Day18::solve(input, Part::Two), Ok(Answer::from(12u64)));
+        }
+    }
+
+    #[test]
+    fn test_cube_in_hollow_cube() {
+        // A hollow cube of 5 with a single cube floating in the middle.
+        let mut cubes = vec![String::from("2,2,2")];
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..5 {
+                    if [x, y, z].iter().any(|side| *side == 0 || *side == 4) {
+                        cubes.push(format!("{x},{y},{z}"));
+                    }
                 }
             }
         }
+        let input = cubes.join("\n");
+
+        assert_eq!(Day18::solve(&input, Part::One), Ok(Answer::from(210u64)));
+        assert_eq!(Day18::solve(&input, Part::Two), Ok(Answer::from(150u64)));
+    }
+
+    #[test]
+    fn test_part_one_test_data() {
+        assert_eq!(Day18::solve(INPUT_TEST, Part::One), Ok(Answer::from(64u64)))
     }
 
-    println!("Free {free_faces}");
+    #[test]
+    fn test_part_one() {
+        assert_eq!(Day18::solve(INPUT, Part::One), Ok(Answer::from(3610u64)))
+    }
+
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(Day18::solve(INPUT_TEST, Part::Two), Ok(Answer::from(58u64)))
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(Day18::solve(INPUT, Part::Two), Ok(Answer::from(2082u64)))
+    }
 }
@@ -1,57 +1,78 @@
 use std::collections::HashMap;
 
-static INPUT: &str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::error::{self, Error},
+};
 
-pub fn part_one() -> usize {
-    let point_map: HashMap<char, usize> =
-        HashMap::from_iter([('A', 1), ('B', 2), ('C', 3), ('X', 1), ('Y', 2), ('Z', 3)]);
+pub struct Day2;
 
-    // Rock, Paper, Scissors
-    let win_map = [[3, 6, 0], [0, 3, 6], [6, 0, 3]];
+impl Solution for Day2 {
+    /// Points of the enemy and own column per round.
+    type Parsed = Vec<(usize, usize)>;
 
-    let mut all_points = 0;
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let point_map: HashMap<char, usize> =
+            HashMap::from_iter([('A', 1), ('B', 2), ('C', 3), ('X', 1), ('Y', 2), ('Z', 3)]);
 
-    for line in INPUT.lines() {
-        let enemy_point = point_map.get(&line.chars().nth(0).unwrap()).unwrap();
-        let own_point = point_map.get(&line.chars().nth(2).unwrap()).unwrap();
-        let win_points = win_map[enemy_point.to_owned() - 1][own_point.to_owned() - 1];
-        all_points += win_points + own_point;
-    }
+        let point = |line: &str, n: usize| {
+            let token = error::nth_char(line, n);
+            token
+                .chars()
+                .next()
+                .and_then(|character| point_map.get(&character))
+                .copied()
+                .ok_or_else(|| Error::at(token, format!("expected a shape in `{line}`")))
+        };
 
-    all_points
-}
+        input
+            .lines()
+            .map(|line| Ok((point(line, 0)?, point(line, 2)?)))
+            .collect()
+    }
 
-pub fn part_two() -> usize {
-    let point_map: HashMap<char, usize> =
-        HashMap::from_iter([('A', 1), ('B', 2), ('C', 3), ('X', 1), ('Y', 2), ('Z', 3)]);
+    fn part_one(rounds: &Self::Parsed) -> Answer {
+        // Rock, Paper, Scissors
+        let win_map = [[3, 6, 0], [0, 3, 6], [6, 0, 3]];
 
-    // Rock, Paper, Scissors => Loose, Draw, Win
-    let win_map = [[3, 4, 8], [1, 5, 9], [2, 6, 7]];
+        let mut all_points = 0;
 
-    let mut all_points = 0;
+        for (enemy_point, own_point) in rounds {
+            let win_points = win_map[enemy_point - 1][own_point - 1];
+            all_points += win_points + own_point;
+        }
 
-    for line in INPUT.lines() {
-        let enemy_point = point_map.get(&line.chars().nth(0).unwrap()).unwrap();
-        let own_point = point_map.get(&line.chars().nth(2).unwrap()).unwrap();
-        let win_points = win_map[enemy_point.to_owned() - 1][own_point.to_owned() - 1];
-        all_points += win_points;
+        all_points.into()
     }
 
-    all_points
+    fn part_two(rounds: &Self::Parsed) -> Answer {
+        // Rock, Paper, Scissors => Loose, Draw, Win
+        let win_map = [[3, 4, 8], [1, 5, 9], [2, 6, 7]];
+
+        let mut all_points = 0;
+
+        for (enemy_point, own_point) in rounds {
+            let win_points = win_map[enemy_point - 1][own_point - 1];
+            all_points += win_points;
+        }
+
+        all_points.into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::part_one;
-    use super::part_two;
+    use super::Day2;
+    use crate::solution::{Answer, Part, Solution};
+    static INPUT: &str = include_str!("input.txt");
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(), 14163)
+        assert_eq!(Day2::solve(INPUT, Part::One), Ok(Answer::from(14163)))
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(), 12091)
+        assert_eq!(Day2::solve(INPUT, Part::Two), Ok(Answer::from(12091)))
     }
 }
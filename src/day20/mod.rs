@@ -1,516 +1,132 @@
-use std::{
-    cell::RefCell,
-    rc::{Rc, Weak},
-    time::Instant,
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        circle::Circle,
+        error::{self, Error},
+    },
 };
 
-static INPUT: &str = include_str!("input.txt");
+/// About the square root of the length of the real input.
+const BLOCK_SIZE: usize = 64;
+const DECRYPTION_KEY: i64 = 811589153;
 
-static BUCKET_SIZE: usize = 100;
-
-#[derive(Debug)]
-struct Element<T> {
-    pub prev: Option<Rc<RefCell<Element<T>>>>,
-    pub next: Option<Rc<RefCell<Element<T>>>>,
-    pub bucket: Option<Weak<RefCell<Bucket<T>>>>,
-    pub value: T,
-}
-
-impl<T> Element<T> {
-    pub fn new(value: T) -> Self {
-        Self {
-            prev: None,
-            next: None,
-            bucket: None,
-            value,
+/**
+ * Mixes the values multiplied by `key` `rounds` times and sums up the grove coordinates.
+ * Fails if a value times the key or the sum do not fit.
+ */
+fn mix(values: &[i64], key: i64, rounds: usize, block_size: usize) -> Result<i64, Error> {
+    let mut circle = Circle::with_block_size(block_size);
+    for value in values {
+        let value = value
+            .checked_mul(key)
+            .ok_or_else(|| Error::new(format!("{value} times the key {key} is too large")))?;
+        circle.push(value);
+    }
+
+    for _ in 0..rounds {
+        for id in 0..circle.len() {
+            circle.move_by(id, circle[id]);
         }
     }
 
-    pub fn bucket(&self) -> Rc<RefCell<Bucket<T>>> {
-        self.bucket.as_ref().unwrap().upgrade().unwrap()
-    }
-}
+    let zero = values.iter().position(|value| *value == 0).unwrap();
+    let zero = circle.position(zero);
 
-#[derive(Debug)]
-struct Bucket<T> {
-    pub offset: usize,
-    pub idx: usize,
-    pub head: Rc<RefCell<Element<T>>>,
-    pub tail: Rc<RefCell<Element<T>>>,
+    [1000, 2000, 3000]
+        .into_iter()
+        .try_fold(0i64, |sum, offset| {
+            sum.checked_add(*circle.get(zero + offset))
+        })
+        .ok_or_else(|| Error::new("the grove coordinates add up to too much"))
 }
 
-impl<T> Bucket<T> {
-    // pub fn len(&self) -> usize {
-    //     let mut len = 0;
+pub struct Day20;
 
-    //     let mut current = Rc::clone(&self.head);
-    //     loop {
-    //         len += 1;
-    //         let next = current.borrow().next.as_ref().map(Rc::clone);
-    //         match next {
-    //             Some(next) => current = Rc::clone(&next),
-    //             None => break,
-    //         }
-    //     }
+impl Solution for Day20 {
+    type Parsed = Vec<i64>;
 
-    //     len
-    // }
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let values: Vec<i64> = input.lines().map(error::number).collect::<Result<_, _>>()?;
 
-    /**
-     * Simply connects the previous neighbors.
-     */
-    pub fn remove(&mut self, element: &Rc<RefCell<Element<T>>>) {
-        let element = element.borrow_mut();
-
-        // Next -> Prev
-        if let Some(next) = &element.next {
-            match &element.prev {
-                None => {
-                    self.head = Rc::clone(next);
-                    next.borrow_mut().prev = None;
-                }
-                Some(prev) => {
-                    next.borrow_mut().prev = Some(Rc::clone(prev));
-                }
-            }
+        if !values.contains(&0) {
+            return Err(Error::new("there is no 0 to start counting from"));
         }
 
-        // Prev -> Next
-        if let Some(prev) = &element.prev {
-            match &element.next {
-                None => {
-                    self.tail = Rc::clone(prev);
-                    prev.borrow_mut().next = None;
-                }
-                Some(next) => {
-                    prev.borrow_mut().next = Some(Rc::clone(next));
-                }
-            }
-        }
+        Ok(values)
     }
 
-    pub fn pop_front(&mut self) -> Rc<RefCell<Element<T>>> {
-        let head = Rc::clone(&self.head);
-
-        match head.borrow().next.as_ref() {
-            None => panic!(),
-            Some(next) => {
-                next.borrow_mut().prev = None;
-                self.head = Rc::clone(next);
-            }
-        }
-
-        head
+    fn part_one(values: &Self::Parsed) -> Answer {
+        mix(values, 1, 1, BLOCK_SIZE).into()
     }
 
-    pub fn pop_back(&mut self) -> Rc<RefCell<Element<T>>> {
-        let tail = Rc::clone(&self.tail);
-
-        match tail.borrow().prev.as_ref() {
-            None => panic!(),
-            Some(prev) => {
-                prev.borrow_mut().next = None;
-                self.tail = Rc::clone(prev);
-            }
-        }
-
-        tail
-    }
-
-    pub fn push_front(&mut self, element: &Rc<RefCell<Element<T>>>) {
-        self.head.borrow_mut().prev = Some(Rc::clone(element));
-        element.borrow_mut().prev = None;
-        element.borrow_mut().next = Some(Rc::clone(&self.head));
-        self.head = Rc::clone(element);
-    }
-
-    pub fn push_back(&mut self, element: &Rc<RefCell<Element<T>>>) {
-        self.tail.borrow_mut().next = Some(Rc::clone(element));
-        element.borrow_mut().next = None;
-        element.borrow_mut().prev = Some(Rc::clone(&self.tail));
-        self.tail = Rc::clone(element);
-    }
-
-    /**
-     * `element` is inserted right of `insert_at`
-     */
-    pub fn insert_at_element(
-        &mut self,
-        element: &Rc<RefCell<Element<T>>>,
-        insert_at: &Rc<RefCell<Element<T>>>,
-    ) {
-        let mut current = element.borrow_mut();
-
-        // Current -> Swap.Next
-        // Swap.Next -> Current
-        match &insert_at.borrow().next {
-            None => {
-                current.next = None;
-                self.tail = Rc::clone(element);
-            }
-            Some(next) => {
-                next.borrow_mut().prev = Some(Rc::clone(element));
-                current.next = Some(Rc::clone(next));
-            }
-        }
-
-        // Connect Swap -> Current
-        current.prev = Some(Rc::clone(insert_at));
-        insert_at.borrow_mut().next = Some(Rc::clone(element));
-    }
-
-    pub fn element_at_offset_from_front(
-        element: &Rc<RefCell<Element<T>>>,
-        initial: usize,
-    ) -> Option<Rc<RefCell<Element<T>>>> {
-        let mut offset = initial;
-
-        let mut current = Rc::clone(element);
-        loop {
-            if offset == 0 {
-                return Some(current);
-            }
-
-            let next = current.borrow().next.as_ref().map(Rc::clone);
-            match next {
-                Some(next) => current = Rc::clone(&next),
-                None => return None,
-            }
-
-            offset -= 1;
-        }
+    fn part_two(values: &Self::Parsed) -> Answer {
+        mix(values, DECRYPTION_KEY, 10, BLOCK_SIZE).into()
     }
+}
 
-    pub fn element_at_offset_from_back(
-        element: &Rc<RefCell<Element<T>>>,
-        offset: usize,
-    ) -> Option<Rc<RefCell<Element<T>>>> {
-        let mut offset = offset;
+#[cfg(test)]
+mod tests {
+    use super::{mix, Day20, DECRYPTION_KEY};
+    use crate::solution::{Answer, Part, Solution};
 
-        let mut current = Rc::clone(element);
-        loop {
-            if offset == 0 {
-                return Some(current);
-            }
+    static INPUT_TEST: &str = include_str!("input-test.txt");
+    static INPUT_TEST_2: &str = include_str!("input-test-2.txt");
+    static INPUT: &str = include_str!("input.txt");
 
-            let prev = current.borrow().prev.as_ref().map(Rc::clone);
-            match prev {
-                Some(prev) => current = Rc::clone(&prev),
-                None => return None,
-            }
+    #[test]
+    fn test_mix_other_sample() {
+        let values = Day20::parse(INPUT_TEST_2).unwrap();
 
-            offset -= 1;
-        }
+        assert_eq!(mix(&values, 1, 1, 64), Ok(3));
     }
 
-    pub fn insert_at(&mut self, element: &Rc<RefCell<Element<T>>>, position: usize) {
-        let target = if position < BUCKET_SIZE / 2 {
-            Bucket::element_at_offset_from_front(&self.head, position).unwrap()
-        } else {
-            Bucket::element_at_offset_from_back(&self.tail, BUCKET_SIZE - 1 - position).unwrap()
-        };
-
-        self.insert_at_element(element, &target);
-    }
-}
+    #[test]
+    fn test_any_block_size() {
+        let values = Day20::parse(INPUT_TEST).unwrap();
 
-impl<T: std::fmt::Debug> Bucket<T> {
-    pub fn display(&self) -> String {
-        let mut next = Some(Rc::clone(&self.head));
-        let mut output = String::new();
-        loop {
-            match next {
-                None => break,
-                Some(current) => {
-                    output = format!("{output}{:?} ", current.borrow().value);
-                    next = current.borrow().next.as_ref().map(Rc::clone);
-                }
-            }
+        for block_size in [1, 2, 3, 7, 100] {
+            assert_eq!(mix(&values, 1, 1, block_size), Ok(3));
+            assert_eq!(mix(&values, DECRYPTION_KEY, 10, block_size), Ok(1623178306));
         }
-
-        output
     }
-}
 
-struct ShiftList {
-    pub bucket_size: usize,
-    pub len: i32,
-    pub buckets: Vec<Rc<RefCell<Bucket<i32>>>>,
-    pub all_elements: Vec<Rc<RefCell<Element<i32>>>>,
-}
-
-impl ShiftList {
-    pub fn new(input: &str, bucket_size: usize) -> Self {
-        let values: Vec<i32> = input.lines().map(|line| line.parse().unwrap()).collect();
-        let value_amount = values.len() as i32;
-        assert!(value_amount % BUCKET_SIZE as i32 == 0);
-
-        let mut buckets = Vec::with_capacity((values.len() / BUCKET_SIZE) + 1);
-        let mut all_elements = Vec::with_capacity(values.len());
-
-        for (i, chunk) in values.chunks(BUCKET_SIZE).enumerate() {
-            // TODO: Do this initially?
-            let elements: Vec<_> = chunk
-                .iter()
-                .copied()
-                .map(Element::new)
-                .map(RefCell::new)
-                .map(Rc::new)
-                .collect();
-
-            let bucket = Rc::new(RefCell::new(Bucket {
-                offset: i * BUCKET_SIZE,
-                idx: i,
-                head: Rc::clone(&elements[0]),
-                tail: Rc::clone(&elements[elements.len() - 1]),
-            }));
-
-            elements[0].borrow_mut().bucket = Some(Rc::downgrade(&bucket));
+    #[test]
+    fn test_overflow() {
+        let input = "0\n4611686018427387904\n4611686018427387904";
 
-            for j in 1..elements.len() {
-                let mut element = elements[j].borrow_mut();
-                element.bucket = Some(Rc::downgrade(&bucket));
-                element.prev = Some(Rc::clone(&elements[j - 1]));
-            }
-
-            for j in 0..(elements.len() - 1) {
-                let mut element = elements[j].borrow_mut();
-                element.next = Some(Rc::clone(&elements[j + 1]));
-            }
-
-            all_elements.extend(elements);
-            buckets.push(bucket);
-        }
-
-        ShiftList {
-            bucket_size,
-            buckets,
-            len: all_elements.len() as i32,
-            all_elements,
-        }
+        assert_eq!(
+            Day20::solve(input, Part::One).unwrap().to_string(),
+            "error: the grove coordinates add up to too much"
+        );
+        assert_eq!(
+            Day20::solve(input, Part::Two).unwrap().to_string(),
+            "error: 4611686018427387904 times the key 811589153 is too large"
+        );
     }
 
-    pub fn shift(&self) {
-        for element in &self.all_elements {
-            let (global_origin_idx, global_target_idx) = self.get_origin_and_target_idx(element);
-
-            if global_origin_idx == global_target_idx {
-                continue;
-            }
-
-            let target_bucket_idx = global_target_idx as usize / self.bucket_size;
-            let local_target_idx = global_target_idx as usize % self.bucket_size;
-
-            let origin_bucket = element.borrow().bucket();
-            let mut origin_bucket = origin_bucket.borrow_mut();
-
-            // If move happens in same bucket, just move inside of bucket
-            if origin_bucket.idx == target_bucket_idx {
-                let old_prev = element.borrow_mut().prev.as_ref().map(Rc::clone);
-                let old_next = element.borrow_mut().next.as_ref().map(Rc::clone);
-
-                origin_bucket.insert_at(element, local_target_idx);
-
-                if let Some(next) = &old_next {
-                    match &old_prev {
-                        None => {
-                            origin_bucket.head = Rc::clone(next);
-                            next.borrow_mut().prev = None;
-                        }
-                        Some(prev) => next.borrow_mut().prev = Some(Rc::clone(prev)),
-                    }
-                }
-                if let Some(prev) = &old_prev {
-                    match &old_next {
-                        None => {
-                            origin_bucket.tail = Rc::clone(prev);
-                            prev.borrow_mut().next = None;
-                        }
-                        Some(next) => {
-                            prev.borrow_mut().next = Some(Rc::clone(next));
-                        }
-                    }
-                }
-
-                continue;
-            }
-
-            origin_bucket.remove(element);
-
-            self.buckets[target_bucket_idx]
-                .borrow_mut()
-                .insert_at(element, local_target_idx);
-
-            element.borrow_mut().bucket = Some(Rc::downgrade(&self.buckets[target_bucket_idx]));
-
-            // TODO: Beautify
-            // Re-Adjustment
-
-            // We check which move direction would result in less operations.
-            let distance_to_target_left = if global_target_idx < global_origin_idx {
-                global_origin_idx - global_target_idx
-            } else {
-                global_origin_idx + (self.len - global_target_idx)
-            };
-
-            let distance_to_target_right = if global_target_idx > global_origin_idx {
-                global_target_idx - global_origin_idx
-            } else {
-                (self.len - global_origin_idx) + global_target_idx
-            };
-
-            if distance_to_target_left < distance_to_target_right {
-                let mut idx = target_bucket_idx;
-                loop {
-                    let back = self.buckets[idx].borrow_mut().pop_back();
-
-                    // Wrap around
-                    let next_idx = if idx == self.buckets.len() - 1 {
-                        0
-                    } else {
-                        idx + 1
-                    };
-
-                    if next_idx == origin_bucket.idx {
-                        origin_bucket.push_front(&back);
-                        back.borrow_mut().bucket = Some(Rc::downgrade(&self.buckets[next_idx]));
-                        break;
-                    }
-
-                    self.buckets[next_idx].borrow_mut().push_front(&back);
-                    back.borrow_mut().bucket = Some(Rc::downgrade(&self.buckets[next_idx]));
-
-                    idx = next_idx;
-                }
-            } else {
-                let mut idx = target_bucket_idx;
-                loop {
-                    let front = self.buckets[idx].borrow_mut().pop_front();
-
-                    // Wrap around
-                    let next_idx = if idx == 0 {
-                        self.buckets.len() - 1
-                    } else {
-                        idx - 1
-                    };
-
-                    if next_idx == origin_bucket.idx {
-                        origin_bucket.push_back(&front);
-                        front.borrow_mut().bucket = Some(Rc::downgrade(&self.buckets[next_idx]));
-                        break;
-                    }
-
-                    self.buckets[next_idx].borrow_mut().push_back(&front);
-                    front.borrow_mut().bucket = Some(Rc::downgrade(&self.buckets[next_idx]));
-
-                    idx = next_idx;
-                }
-            }
-        }
+    #[test]
+    fn test_part_one_test_data() {
+        assert_eq!(Day20::solve(INPUT_TEST, Part::One), Ok(Answer::from(3u64)))
     }
 
-    fn get_origin_and_target_idx(&self, element: &Rc<RefCell<Element<i32>>>) -> (i32, i32) {
-        let local_origin_idx = {
-            let mut distance_to_head = 0;
-            let mut current = Rc::clone(element);
-            loop {
-                let prev = current.borrow().prev.as_ref().map(Rc::clone);
-                match prev {
-                    Some(prev) => current = prev,
-                    None => break,
-                };
-                distance_to_head += 1;
-            }
-            distance_to_head
-        };
-
-        let element = element.borrow();
-        let global_origin_idx = (element.bucket().borrow().offset + local_origin_idx) as i32;
-
-        // We can skip all the moves that would wrap one (ore more) complete round.
-        let moves = element.value.abs() % self.len;
-
-        let mut target_index = if element.value < 0 {
-            // TODO: Explain
-            global_origin_idx - moves - 1
-        } else {
-            global_origin_idx + moves
-        };
-
-        target_index %= self.len;
-        if target_index < 0 {
-            target_index = self.len - target_index.abs()
-        }
-
-        (global_origin_idx, target_index)
+    #[test]
+    fn test_part_one() {
+        assert_eq!(Day20::solve(INPUT, Part::One), Ok(Answer::from(3700u64)))
     }
 
-    pub fn get_value_at_offset(&self, start: &Rc<RefCell<Element<i32>>>, offset: usize) -> i32 {
-        // Find offset until end of bucket
-        let mut counter = 0;
-        let mut current = Rc::clone(start);
-        loop {
-            let next = current.borrow().next.as_ref().map(Rc::clone);
-            match next {
-                Some(next) => current = next,
-                None => break,
-            }
-
-            counter += 1;
-        }
-
-        // Calculate target with the rest
-        let offset = offset - counter;
-
-        let origin_bucket_idx = start.borrow().bucket().borrow().idx;
-
-        let buckets_traversed = offset / BUCKET_SIZE;
-        let target_local_index = (offset % BUCKET_SIZE) - 1;
-        let buckets_offset = buckets_traversed % self.buckets.len();
-
-        let target_bucket_idx = (origin_bucket_idx + buckets_offset) % self.buckets.len();
-        let target_element = Bucket::element_at_offset_from_front(
-            &self.buckets[target_bucket_idx].borrow().head,
-            target_local_index,
-        );
-
-        target_element.unwrap().borrow().value
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(
+            Day20::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(1623178306u64))
+        )
     }
-}
-
-/**
- * TODO:
- * - Don't borrow so much?
- * - Some unnecessary copies?
- */
-pub fn part_one() {
-    let shift_list = ShiftList::new(INPUT, BUCKET_SIZE);
 
-    let zero = shift_list
-        .all_elements
-        .iter()
-        .find(|element| element.borrow().value == 0)
-        .map(Rc::clone)
-        .unwrap();
-
-    shift_list.shift();
-
-    let mut sum = 0;
-    for offset in [1000, 2000, 3000] {
-        sum += shift_list.get_value_at_offset(&zero, offset);
+    #[test]
+    fn test_part_two() {
+        assert_eq!(
+            Day20::solve(INPUT, Part::Two),
+            Ok(Answer::from(10626948369382u64))
+        )
     }
-
-    println!("{sum}");
 }
-
-// #[cfg(test)]
-// mod tests {
-//     use super::{part_one, INPUT};
-
-//     #[test]
-//     fn test_part_one() {
-//         assert_eq!(part_one(), 3)
-//     }
-
-//     // test-2: 0 3 1 + -2 2 -3 +
-// }
@@ -1,12 +1,14 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     str::FromStr,
-    string::ParseError,
 };
 
-static INPUT: &str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::error::{self, Error},
+};
 
-enum Operation {
+pub enum Operation {
     Add,
     Sub,
     Mul,
@@ -14,7 +16,7 @@ enum Operation {
 }
 
 impl FromStr for Operation {
-    type Err = ParseError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -22,12 +24,12 @@ impl FromStr for Operation {
             "-" => Ok(Operation::Sub),
             "*" => Ok(Operation::Mul),
             "/" => Ok(Operation::Div),
-            _ => unimplemented!(),
+            _ => Err(Error::at(s, format!("unknown operation `{s}`"))),
         }
     }
 }
 
-struct Calculation {
+pub struct Calculation {
     pub name: String,
     pub lhs: String,
     pub rhs: String,
@@ -35,71 +37,178 @@ struct Calculation {
 }
 
 impl Calculation {
-    pub fn solve(&self, lhs: usize, rhs: usize) -> usize {
+    /// `None` if the result overflows or is divided by zero.
+    pub fn solve(&self, lhs: i64, rhs: i64) -> Option<i64> {
         match self.operation {
-            Operation::Add => lhs + rhs,
-            Operation::Sub => lhs - rhs,
-            Operation::Mul => lhs * rhs,
-            Operation::Div => lhs / rhs,
+            Operation::Add => lhs.checked_add(rhs),
+            Operation::Sub => lhs.checked_sub(rhs),
+            Operation::Mul => lhs.checked_mul(rhs),
+            Operation::Div => lhs.checked_div(rhs),
         }
     }
 
-    pub fn solve_lhs(&self, result: usize, rhs: usize) -> usize {
+    pub fn solve_lhs(&self, result: i64, rhs: i64) -> Option<i64> {
         match self.operation {
-            Operation::Add => result - rhs,
-            Operation::Sub => result + rhs,
-            Operation::Mul => result / rhs,
-            Operation::Div => result * rhs,
+            Operation::Add => result.checked_sub(rhs),
+            Operation::Sub => result.checked_add(rhs),
+            Operation::Mul => result.checked_div(rhs),
+            Operation::Div => result.checked_mul(rhs),
         }
     }
 
-    pub fn solve_rhs(&self, result: usize, lhs: usize) -> usize {
+    pub fn solve_rhs(&self, result: i64, lhs: i64) -> Option<i64> {
         match self.operation {
-            Operation::Add => result - lhs,
-            Operation::Sub => lhs - result,
-            Operation::Mul => result / lhs,
-            Operation::Div => lhs / result,
+            Operation::Add => result.checked_sub(lhs),
+            Operation::Sub => lhs.checked_sub(result),
+            Operation::Mul => result.checked_div(lhs),
+            Operation::Div => lhs.checked_div(result),
         }
     }
+
+    fn overflow(&self) -> Error {
+        Error::new(format!(
+            "the number of `{}` overflows or is divided by zero",
+            self.name
+        ))
+    }
 }
 
-pub fn part_one() {
-    find_root_value(INPUT);
+pub struct Jobs {
+    constants: HashMap<String, i64>,
+    calculations: HashMap<String, Calculation>,
+}
+
+impl Jobs {
+    /// The constants, borrowed so that calculated values can be added next to them.
+    fn constants(&self) -> HashMap<&str, i64> {
+        self.constants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect()
+    }
+}
+
+impl FromStr for Jobs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<_> = s.lines().collect();
+        let mut constants = HashMap::with_capacity(lines.len());
+        let mut calculations = HashMap::with_capacity(lines.len() / 2);
+        // The monkeys that others wait for, which are only known once all lines are read.
+        let mut references = Vec::with_capacity(lines.len());
+        let mut waits_for = Vec::with_capacity(lines.len() / 2);
+
+        for line in lines {
+            let (name, value) = error::split_once(line, ":")?;
+            let value = value.trim();
+
+            match value.parse::<i64>() {
+                Ok(value) => {
+                    constants.insert(name.to_string(), value);
+                }
+                Err(_) => {
+                    let mut parts = value.split_whitespace();
+
+                    let lhs = error::next(&mut parts, value, "a monkey")?;
+                    let operation = error::next(&mut parts, value, "an operation")?.parse()?;
+                    let rhs = error::next(&mut parts, value, "a monkey")?;
+                    references.extend([lhs, rhs]);
+                    waits_for.push((name, [lhs, rhs]));
+
+                    let calculation = Calculation {
+                        name: name.to_string(),
+                        lhs: lhs.to_string(),
+                        rhs: rhs.to_string(),
+                        operation,
+                    };
+
+                    calculations.insert(name.to_string(), calculation);
+                }
+            }
+        }
+
+        if let Some(name) = references
+            .into_iter()
+            .find(|name| !constants.contains_key(*name) && !calculations.contains_key(*name))
+        {
+            return Err(Error::at(name, format!("there is no monkey `{name}`")));
+        }
+
+        if !calculations.contains_key("root") {
+            return Err(Error::new("the monkey `root` is missing"));
+        }
+        if !constants.contains_key("humn") {
+            return Err(Error::new(
+                "the monkey `humn` is missing or does not yell a number",
+            ));
+        }
+        if let Some(name) = find_cycle(&waits_for) {
+            return Err(Error::at(
+                name,
+                format!("`{name}` waits for its own number"),
+            ));
+        }
+
+        Ok(Jobs {
+            constants,
+            calculations,
+        })
+    }
 }
 
 /**
- * Try:
- * - HashMap::from() may be faster?
- * - Some way of not copying all the strings?
+ * A monkey that waits for itself, through any number of others, or `None`. It is pointed at
+ * where it is waited for, the search follows the order of `waits_for` to always find the
+ * same one.
  */
-pub fn find_root_value(input: &str) -> usize {
-    let lines: Vec<_> = input.lines().collect();
-    let mut constants = HashMap::with_capacity(lines.len());
-    let mut calculations = HashMap::with_capacity(lines.len() / 2);
-
-    for line in lines {
-        let (name, value) = line.split_once(':').unwrap();
-        let value = value.trim();
-
-        match value.parse::<usize>() {
-            Ok(value) => {
-                constants.insert(name, value);
-            }
-            Err(_) => {
-                let parts: Vec<_> = value.split_whitespace().collect();
+fn find_cycle<'a>(waits_for: &[(&'a str, [&'a str; 2])]) -> Option<&'a str> {
+    let operands: HashMap<&str, [&str; 2]> = waits_for.iter().copied().collect();
+    let mut done = HashSet::with_capacity(operands.len());
+    let mut waiting = HashSet::new();
 
-                let calculation = Calculation {
-                    name: name.to_string(),
-                    lhs: parts[0].to_string(),
-                    operation: parts[1].parse().unwrap(),
-                    rhs: parts[2].to_string(),
-                };
+    for (start, _) in waits_for {
+        if done.contains(start) {
+            continue;
+        }
 
-                calculations.insert(name, calculation);
+        // The monkeys waiting for each other so far and the next operand of each to visit.
+        let mut stack = vec![(*start, 0)];
+        waiting.insert(*start);
+
+        while let Some((name, next)) = stack.last_mut() {
+            let name = *name;
+            match operands[name].get(*next) {
+                Some(&operand) => {
+                    *next += 1;
+                    if waiting.contains(operand) {
+                        return Some(operand);
+                    }
+                    if operands.contains_key(operand) && !done.contains(operand) {
+                        waiting.insert(operand);
+                        stack.push((operand, 0));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    waiting.remove(name);
+                    done.insert(name);
+                }
             }
         }
     }
 
+    None
+}
+
+/**
+ * Try:
+ * - Some way of not copying all the strings?
+ */
+fn find_root_value(jobs: &Jobs) -> Result<i64, Error> {
+    let mut constants = jobs.constants();
+    let calculations = &jobs.calculations;
+
     let mut to_calculate = Vec::with_capacity(calculations.len());
 
     let root = calculations.get("root").unwrap();
@@ -121,54 +230,20 @@ pub fn find_root_value(input: &str) -> usize {
         let lhs = constants.get(calculation.lhs.as_str()).unwrap();
         let rhs = constants.get(calculation.rhs.as_str()).unwrap();
 
-        let value = match calculation.operation {
-            Operation::Add => lhs + rhs,
-            Operation::Sub => lhs - rhs,
-            Operation::Mul => lhs * rhs,
-            Operation::Div => lhs / rhs,
-        };
+        let value = calculation
+            .solve(*lhs, *rhs)
+            .ok_or_else(|| calculation.overflow())?;
 
         constants.insert(&calculation.name, value);
     }
 
-    *constants.get("root").unwrap()
-}
-
-pub fn part_two() -> usize {
-    find_human_input(INPUT)
+    Ok(*constants.get("root").unwrap())
 }
 
-pub fn find_human_input(input: &str) -> usize {
-    let lines: Vec<_> = input.lines().collect();
-
-    let mut constants = HashMap::with_capacity(lines.len());
-    let mut calculations = HashMap::with_capacity(lines.len() / 2);
-
-    for line in lines {
-        let (name, value) = line.split_once(':').unwrap();
-        if name == "humn" {
-            continue;
-        }
-
-        match value.trim().parse::<usize>() {
-            Ok(value) => {
-                constants.insert(name, value);
-            }
-            Err(_) => {
-                let parts: Vec<_> = value.split_whitespace().collect();
-
-                calculations.insert(
-                    name,
-                    Calculation {
-                        name: name.to_string(),
-                        lhs: parts[0].to_string(),
-                        operation: parts[1].parse().unwrap(),
-                        rhs: parts[2].to_string(),
-                    },
-                );
-            }
-        }
-    }
+fn find_human_input(jobs: &Jobs) -> Result<i64, Error> {
+    let mut constants = jobs.constants();
+    constants.remove("humn");
+    let calculations = &jobs.calculations;
 
     let mut to_calculate = Vec::with_capacity(calculations.len());
 
@@ -204,7 +279,9 @@ pub fn find_human_input(input: &str) -> usize {
 
         let lhs = constants.get(calculation.lhs.as_str()).unwrap();
         let rhs = constants.get(calculation.rhs.as_str()).unwrap();
-        let value = calculation.solve(*lhs, *rhs);
+        let value = calculation
+            .solve(*lhs, *rhs)
+            .ok_or_else(|| calculation.overflow())?;
 
         constants.insert(&calculation.name, value);
     }
@@ -220,7 +297,9 @@ pub fn find_human_input(input: &str) -> usize {
                     constants.insert(&calculation.lhs, *rhs);
                 } else {
                     let result = constants.get(calculation.name.as_str()).unwrap();
-                    let lhs = calculation.solve_lhs(*result, *rhs);
+                    let lhs = calculation
+                        .solve_lhs(*result, *rhs)
+                        .ok_or_else(|| calculation.overflow())?;
                     constants.insert(&calculation.lhs, lhs);
                 }
             }
@@ -229,40 +308,126 @@ pub fn find_human_input(input: &str) -> usize {
                     constants.insert(&calculation.rhs, *lhs);
                 } else {
                     let result = constants.get(calculation.name.as_str()).unwrap();
-                    let rhs = calculation.solve_rhs(*result, *lhs);
+                    let rhs = calculation
+                        .solve_rhs(*result, *lhs)
+                        .ok_or_else(|| calculation.overflow())?;
                     constants.insert(&calculation.rhs, rhs);
                 }
             }
-            _ => unreachable!(),
+            _ => {
+                return Err(Error::new(format!(
+                    "`humn` is waited for on both sides of `{}`",
+                    calculation.name
+                )))
+            }
         }
     }
 
-    *constants.get("humn").unwrap()
+    constants
+        .get("humn")
+        .copied()
+        .ok_or_else(|| Error::new("`root` does not wait for `humn`"))
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Parsed = Jobs;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        input.parse()
+    }
+
+    fn part_one(jobs: &Self::Parsed) -> Answer {
+        find_root_value(jobs).into()
+    }
+
+    fn part_two(jobs: &Self::Parsed) -> Answer {
+        find_human_input(jobs).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{find_human_input, find_root_value};
+    use super::Day21;
+    use crate::solution::{Answer, Part, Solution};
+
     static INPUT_TEST: &str = include_str!("input-test.txt");
     static INPUT: &str = include_str!("input.txt");
 
+    #[test]
+    fn test_unknown_monkey() {
+        let err = Day21::solve("root: humn + abcd\nhumn: 5", Part::One).unwrap_err();
+
+        assert_eq!(err.message, "there is no monkey `abcd`");
+        assert_eq!(err.position.unwrap().column, 14);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let err =
+            Day21::solve("root: a + b\na: b * c\nb: a - c\nc: 2\nhumn: 5", Part::One).unwrap_err();
+
+        assert_eq!(err.message, "`a` waits for its own number");
+        assert_eq!(err.position.unwrap().line, 3);
+    }
+
+    #[test]
+    fn test_missing_human() {
+        let err = Day21::solve("root: a + b\na: 1\nb: 2", Part::Two).unwrap_err();
+
+        assert_eq!(
+            err.message,
+            "the monkey `humn` is missing or does not yell a number"
+        );
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        let input = "root: a - b\na: 3\nb: 5\nhumn: 1";
+
+        assert_eq!(Day21::solve(input, Part::One), Ok(Answer::from(-2)));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "root: a * b\na: 9223372036854775807\nb: humn + humn\nhumn: 2";
+
+        assert_eq!(
+            Day21::solve(input, Part::One).unwrap().to_string(),
+            "error: the number of `root` overflows or is divided by zero"
+        );
+    }
+
     #[test]
     fn test_part_one_test_data() {
-        assert_eq!(find_root_value(INPUT_TEST), 152)
+        assert_eq!(
+            Day21::solve(INPUT_TEST, Part::One),
+            Ok(Answer::from(152u64))
+        )
     }
 
     #[test]
     fn test_part_one() {
-        assert_eq!(find_root_value(INPUT), 63119856257960)
+        assert_eq!(
+            Day21::solve(INPUT, Part::One),
+            Ok(Answer::from(63119856257960u64))
+        )
     }
 
     #[test]
     fn test_part_two_test_data() {
-        assert_eq!(find_human_input(INPUT_TEST), 301)
+        assert_eq!(
+            Day21::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(301u64))
+        )
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(find_human_input(INPUT), 3006709232464)
+        assert_eq!(
+            Day21::solve(INPUT, Part::Two),
+            Ok(Answer::from(3006709232464u64))
+        )
     }
 }
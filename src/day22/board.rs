@@ -0,0 +1,175 @@
+use super::{Field, Row};
+use crate::util::{
+    geom::{Direction, Point},
+    Error,
+};
+
+/**
+ * The tiles of one row or column, which are always in one piece. Walls are kept as sorted
+ * offsets from `start`, so a walk can jump straight to the next one instead of checking
+ * every tile on the way.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    start: usize,
+    len: usize,
+    walls: Vec<usize>,
+}
+
+impl Line {
+    fn new<'a>(start: usize, tiles: impl Iterator<Item = &'a Field>) -> Self {
+        let mut len = 0;
+        let mut walls = vec![];
+        for (offset, tile) in tiles.enumerate() {
+            if *tile == Field::Wall {
+                walls.push(offset);
+            }
+            len += 1;
+        }
+
+        Line { start, len, walls }
+    }
+
+    /**
+     * Where walking `steps` tiles from `position` ends, towards higher positions if
+     * `forward`. Walks wrap around at the ends of the line and stop in front of walls.
+     */
+    pub fn walk(&self, position: usize, steps: usize, forward: bool) -> usize {
+        let (offset, len) = (position - self.start, self.len);
+
+        // The free tiles up to the next wall, which may be behind the wrap.
+        let free = if forward {
+            let index = self.walls.partition_point(|wall| *wall <= offset);
+            match self.walls.get(index) {
+                Some(wall) => Some(wall - offset - 1),
+                None => self.walls.first().map(|wall| wall + len - offset - 1),
+            }
+        } else {
+            let index = self.walls.partition_point(|wall| *wall < offset);
+            match index.checked_sub(1) {
+                Some(index) => Some(offset - self.walls[index] - 1),
+                None => self.walls.last().map(|wall| offset + len - wall - 1),
+            }
+        };
+
+        let steps = free.map_or(steps, |free| steps.min(free)) % len;
+        let offset = match forward {
+            true => (offset + steps) % len,
+            false => (offset + len - steps) % len,
+        };
+
+        self.start + offset
+    }
+}
+
+/// The board with a [`Line`] for every row and column.
+pub struct Board {
+    rows: Vec<Row>,
+    horizontal: Vec<Line>,
+    vertical: Vec<Line>,
+}
+
+impl Board {
+    pub fn new(rows: Vec<Row>) -> Result<Self, Error> {
+        if rows.is_empty() {
+            return Err(Error::new("the map has no rows"));
+        }
+
+        let horizontal = rows
+            .iter()
+            .map(|row| Line::new(row.start, row.values.iter()))
+            .collect();
+
+        let width = rows.iter().map(|row| row.end + 1).max().unwrap_or(0);
+        let vertical = (0..width)
+            .map(|x| {
+                let contains = |y: &usize| rows[*y].start <= x && x <= rows[*y].end;
+                // Columns without tiles, like blank ones left of the map, are never walked.
+                let Some(start) = (0..rows.len()).find(contains) else {
+                    return Ok(Line::new(0, std::iter::empty()));
+                };
+                let end = (start..rows.len()).take_while(contains).last().unwrap();
+                if (end + 1..rows.len()).any(|y| contains(&y)) {
+                    return Err(Error::new(format!("column {} has a gap", x + 1)));
+                }
+
+                let tiles = (start..=end).map(|y| &rows[y].values[x - rows[y].start]);
+                Ok(Line::new(start, tiles))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Board {
+            rows,
+            horizontal,
+            vertical,
+        })
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn field(&self, position: Point<usize>) -> Option<&Field> {
+        let row = self.rows.get(position.y)?;
+        let x = position.x.checked_sub(row.start)?;
+        row.values.get(x)
+    }
+
+    /// The leftmost tile of the top row.
+    pub fn start(&self) -> Point<usize> {
+        Point::new(self.rows[0].start, 0)
+    }
+
+    /// Walks `steps` tiles with flat wrap-around, see [`Line::walk`].
+    pub fn walk(&self, position: Point<usize>, direction: Direction, steps: usize) -> Point<usize> {
+        let Point { x, y } = position;
+
+        match direction {
+            Direction::Right => Point::new(self.horizontal[y].walk(x, steps, true), y),
+            Direction::Left => Point::new(self.horizontal[y].walk(x, steps, false), y),
+            Direction::Down => Point::new(x, self.vertical[x].walk(y, steps, true)),
+            Direction::Up => Point::new(x, self.vertical[x].walk(y, steps, false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Line;
+    use crate::day22::Field;
+
+    /// `..#...#.` from position 10 on.
+    fn line() -> Line {
+        let tiles: Vec<_> = "..#...#."
+            .chars()
+            .map(|c| Field::from_char(c).unwrap())
+            .collect();
+        Line::new(10, tiles.iter())
+    }
+
+    #[test]
+    fn test_walk_to_walls() {
+        let line = line();
+
+        assert_eq!(line.walk(13, 1, true), 14);
+        assert_eq!(line.walk(13, 100, true), 15);
+        assert_eq!(line.walk(13, 100, false), 13);
+        assert_eq!(line.walk(15, 1_000_000_000, false), 13);
+    }
+
+    #[test]
+    fn test_walk_wraps_around() {
+        let line = line();
+
+        // Over the end and up to the first wall, or over the start backwards.
+        assert_eq!(line.walk(17, 1, true), 10);
+        assert_eq!(line.walk(17, 2, true), 11);
+        assert_eq!(line.walk(17, 100, true), 11);
+        assert_eq!(line.walk(11, 2, false), 17);
+        assert_eq!(line.walk(11, 100, false), 17);
+
+        let open = Line::new(0, [Field::Floor, Field::Floor, Field::Floor].iter());
+        assert_eq!(open.walk(1, 7, true), 2);
+        assert_eq!(open.walk(1, 7, false), 0);
+    }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, fmt};
+
+use super::Row;
+use crate::util::{
+    geom::{Direction, Point, Vector3},
+    Error,
+};
+
+/// Where the axes of a face of the net point once it is folded into the cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Orientation {
+    right: Vector3<i32>,
+    down: Vector3<i32>,
+    /// Points out of the cube.
+    normal: Vector3<i32>,
+}
+
+impl Orientation {
+    fn towards(&self, direction: Direction) -> Vector3<i32> {
+        match direction {
+            Direction::Up => -self.down,
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => -self.right,
+        }
+    }
+
+    /// The direction on the face that points along `vector`, if any does.
+    fn direction_of(&self, vector: Vector3<i32>) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| self.towards(*direction) == vector)
+    }
+
+    /// The axis along an edge that is crossed towards `direction`.
+    fn along(&self, direction: Direction) -> Vector3<i32> {
+        match direction {
+            Direction::Left | Direction::Right => self.down,
+            Direction::Up | Direction::Down => self.right,
+        }
+    }
+
+    /**
+     * The neighbour of the face towards `direction` in the net, folded around the shared
+     * edge. The fold turns the normal to point over the edge and what pointed over the edge
+     * inwards, the axis along the edge stays.
+     */
+    fn fold(&self, direction: Direction) -> Orientation {
+        let edge = self.towards(direction);
+        let turn = |axis: Vector3<i32>| match axis {
+            axis if axis == self.normal => edge,
+            axis if axis == -self.normal => -edge,
+            axis if axis == edge => -self.normal,
+            axis if axis == -edge => self.normal,
+            axis => axis,
+        };
+
+        Orientation {
+            right: turn(self.right),
+            down: turn(self.down),
+            normal: turn(self.normal),
+        }
+    }
+}
+
+/**
+ * Walking off `face` towards `leaving` continues on `to` heading `entering`. If `flipped`,
+ * the position along the edge counts from the other end.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub face: usize,
+    pub leaving: Direction,
+    pub to: usize,
+    pub entering: Direction,
+    pub flipped: bool,
+}
+
+/// The board folded into a cube, with the edges between its faces.
+#[derive(Debug)]
+pub struct Cube {
+    size: usize,
+    /// The top left corner of each face on the board.
+    faces: Vec<Point<usize>>,
+    /// Per face, the edges in the order of [`Direction::ALL`].
+    edges: Vec<[Edge; 4]>,
+}
+
+fn tile_exists(rows: &[Row], x: usize, y: usize) -> bool {
+    rows.get(y)
+        .is_some_and(|row| row.start <= x && x <= row.end)
+}
+
+impl Cube {
+    /**
+     * Finds the six faces in the net and folds them up, starting at the first one. The
+     * layout of the net does not matter, as long as it folds into a cube.
+     */
+    pub fn fold(rows: &[Row]) -> Result<Self, Error> {
+        let tiles: usize = rows.iter().map(|row| row.values.len()).sum();
+        let size = (1..)
+            .take_while(|size| 6 * size * size <= tiles)
+            .last()
+            .filter(|size| 6 * size * size == tiles)
+            .ok_or_else(|| Error::new(format!("{tiles} tiles are not six square faces")))?;
+
+        let width = rows.iter().map(|row| row.end + 1).max().unwrap_or(0);
+        let mut net = HashMap::new();
+        let mut faces = vec![];
+        for y in (0..rows.len()).step_by(size) {
+            for x in (0..width).step_by(size) {
+                if tile_exists(rows, x, y) {
+                    net.insert((x / size, y / size), faces.len());
+                    faces.push(Point::new(x, y));
+                }
+            }
+        }
+
+        let mut orientations: Vec<Option<Orientation>> = vec![None; faces.len()];
+        orientations[0] = Some(Orientation {
+            right: Vector3::new(1, 0, 0),
+            down: Vector3::new(0, 1, 0),
+            normal: Vector3::new(0, 0, -1),
+        });
+        let mut stack = vec![0];
+        while let Some(face) = stack.pop() {
+            let orientation = orientations[face].unwrap();
+            let position = Point::new(faces[face].x / size, faces[face].y / size);
+
+            for direction in Direction::ALL {
+                let Some(neighbour) = position
+                    .x
+                    .checked_add_signed(direction.vector::<isize>().x)
+                    .zip(position.y.checked_add_signed(direction.vector().y))
+                    .and_then(|neighbour| net.get(&neighbour))
+                else {
+                    continue;
+                };
+
+                if orientations[*neighbour].is_none() {
+                    orientations[*neighbour] = Some(orientation.fold(direction));
+                    stack.push(*neighbour);
+                }
+            }
+        }
+
+        let orientations: Vec<Orientation> = orientations.into_iter().flatten().collect();
+        let mut normals: Vec<_> = orientations.iter().map(|face| face.normal).collect();
+        normals.sort();
+        normals.dedup();
+        if faces.len() != 6 || orientations.len() != 6 || normals.len() != 6 {
+            return Err(Error::new("the map does not fold into a cube"));
+        }
+
+        let edges = orientations
+            .iter()
+            .enumerate()
+            .map(|(face, orientation)| {
+                Direction::ALL.map(|leaving| {
+                    let edge = orientation.towards(leaving);
+                    let to = orientations
+                        .iter()
+                        .position(|other| other.normal == edge)
+                        .unwrap();
+                    // Over the edge, what was the inside of the cube is straight ahead.
+                    let entering = orientations[to].direction_of(-orientation.normal).unwrap();
+
+                    Edge {
+                        face,
+                        leaving,
+                        to,
+                        entering,
+                        flipped: orientation.along(leaving) != orientations[to].along(entering),
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Cube { size, faces, edges })
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().flatten()
+    }
+
+    pub fn edge(&self, face: usize, leaving: Direction) -> &Edge {
+        &self.edges[face][leaving as usize]
+    }
+
+    fn face_at(&self, position: Point<usize>) -> usize {
+        self.faces
+            .iter()
+            .position(|corner| {
+                (corner.x..corner.x + self.size).contains(&position.x)
+                    && (corner.y..corner.y + self.size).contains(&position.y)
+            })
+            .unwrap_or_else(|| panic!("{position} is not on a face"))
+    }
+
+    /// Where walking off the board from `position` towards `direction` ends up.
+    pub fn wrap(&self, position: Point<usize>, direction: Direction) -> (Point<usize>, Direction) {
+        let face = self.face_at(position);
+        let edge = self.edge(face, direction);
+        let (from, to) = (self.faces[face], self.faces[edge.to]);
+
+        let along = match direction {
+            Direction::Left | Direction::Right => position.y - from.y,
+            Direction::Up | Direction::Down => position.x - from.x,
+        };
+        let last = self.size - 1;
+        let along = if edge.flipped { last - along } else { along };
+
+        let (x, y) = match edge.entering {
+            Direction::Right => (0, along),
+            Direction::Down => (along, 0),
+            Direction::Left => (last, along),
+            Direction::Up => (along, last),
+        };
+
+        (Point::new(to.x + x, to.y + y), edge.entering)
+    }
+}
+
+/// One edge per line, like `face 0 Up -> face 5 Right`.
+impl fmt::Display for Cube {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, edge) in self.edges().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "face {} {:?} -> face {} {:?}{}",
+                edge.face,
+                edge.leaving,
+                edge.to,
+                edge.entering,
+                if edge.flipped { ", flipped" } else { "" }
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,52 +1,42 @@
-use std::{str::FromStr, string::ParseError, time::Instant};
+use std::str::FromStr;
 
-static INPUT: &str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        error::{self, Error},
+        geom::{Direction, Point},
+    },
+};
 
-enum Direction {
-    Right,
-    Down,
-    Left,
-    Up,
-}
+mod board;
+mod cube;
+
+use board::Board;
+use cube::Cube;
 
-enum Turn {
+pub enum Turn {
     Left,
     Right,
 }
 
-impl Direction {
-    pub fn turn_right(&self) -> Direction {
-        match self {
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Up => Direction::Right,
-        }
-    }
-
-    pub fn turn_left(&self) -> Direction {
-        match self {
-            Direction::Right => Direction::Up,
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Down,
-            Direction::Down => Direction::Right,
-        }
-    }
-}
-
-fn parse_instructions(input: &str) -> (Vec<usize>, Vec<Turn>) {
+fn parse_instructions(input: &str) -> Result<(Vec<usize>, Vec<Turn>), Error> {
     let mut steps = Vec::with_capacity(input.len());
     let mut turns = Vec::with_capacity(input.len());
 
     let mut current_steps: usize = 0;
-    for character in input.chars() {
+    for (i, character) in input.char_indices() {
         if character.is_ascii_digit() {
             current_steps = (current_steps * 10) + character.to_digit(10).unwrap() as usize;
         } else {
-            if character == 'L' {
-                turns.push(Turn::Left)
-            } else {
-                turns.push(Turn::Right)
+            match character {
+                'L' => turns.push(Turn::Left),
+                'R' => turns.push(Turn::Right),
+                _ => {
+                    return Err(Error::at(
+                        &input[i..],
+                        format!("unexpected `{character}` in the path"),
+                    ))
+                }
             };
 
             steps.push(current_steps);
@@ -59,197 +49,141 @@ fn parse_instructions(input: &str) -> (Vec<usize>, Vec<Turn>) {
         steps.push(current_steps);
     }
 
-    (steps, turns)
+    Ok((steps, turns))
 }
 
 #[derive(PartialEq)]
-enum Field {
+pub enum Field {
     Floor,
     Wall,
 }
 
 impl Field {
-    pub fn from_char(c: char) -> Self {
+    pub fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Field::Floor,
-            '#' => Field::Wall,
-            _ => unimplemented!(),
+            '.' => Some(Field::Floor),
+            '#' => Some(Field::Wall),
+            _ => None,
         }
     }
 }
 
-struct Row {
+pub struct Row {
     pub start: usize,
     pub end: usize,
     pub values: Vec<Field>,
 }
 
 impl FromStr for Row {
-    type Err = ParseError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut found_start = false;
-        let mut start = 0;
-        let end = s.len() - 1;
-        let mut values = Vec::with_capacity(s.len());
-
-        for (i, character) in s.chars().enumerate() {
-            if character != ' ' {
-                if !found_start {
-                    start = i;
-                    found_start = true;
-                }
+        let s = s.trim_end_matches(' ');
+        let start = s.len() - s.trim_start_matches(' ').len();
+        let mut values = Vec::with_capacity(s.len() - start);
 
-                values.push(Field::from_char(character));
-            }
+        if start == s.len() {
+            return Err(Error::at(s, "a row needs at least one tile"));
         }
 
-        Ok(Row { start, end, values })
+        for (i, character) in s.char_indices().skip(start) {
+            let field = match character {
+                ' ' => return Err(Error::at(&s[i..], "a row cannot have gaps")),
+                _ => Field::from_char(character).ok_or_else(|| {
+                    Error::at(&s[i..], format!("unexpected `{character}` in the map"))
+                })?,
+            };
+            values.push(field);
+        }
+
+        Ok(Row {
+            start,
+            end: start + values.len() - 1,
+            values,
+        })
     }
 }
 
-struct Position {
-    pub x: usize,
-    pub y: usize,
-}
+fn move_in_2d(notes: &Notes) -> usize {
+    let Notes {
+        board,
+        steps,
+        turns,
+        ..
+    } = notes;
 
-/**
-* Einfach 2D Array mit None, Way oder Hindernis als Pixel
-* Optimierungs-Idee 2:
-* - Double-LinkedList nur mit Hindernissen für jede row und spalte
-* => Dann muss man nicht jeden Pixel überprüfen
-* => Und auch nicht schauen ob man das Ende erreicht hat
-* - => Funktioniert nur, wenn es keine Räume mit Aussparungen dazwischen gibt
-*
-* Räume mit Aussparungen machen eigentlich bei dem Wrap-Anforderungen keinen Sinn
-*
-* =>
-*/
-pub fn part_one() -> usize {
-    move_in_2d(INPUT)
-}
+    let mut current_position = board.start();
+    let mut current_direction = Direction::Right;
 
-// 2.4ms
-pub fn move_in_2d(input: &str) -> usize {
-    let (map, instructions) = input.split_once("\n\n").unwrap();
+    for (i, steps) in steps.iter().enumerate() {
+        current_position = board.walk(current_position, current_direction, *steps);
 
-    let rows: Vec<Row> = map.lines().map(|line| line.parse().unwrap()).collect();
+        // We have one more step than turn
+        if let Some(turn) = turns.get(i) {
+            match turn {
+                Turn::Left => current_direction = current_direction.turn_left(),
+                Turn::Right => current_direction = current_direction.turn_right(),
+            }
+        }
+    }
 
-    let (steps, turns) = parse_instructions(instructions);
+    password(current_position, current_direction)
+}
 
-    let mut current_position = Position {
-        x: rows[0].start,
-        y: 0,
+fn password(position: Point<usize>, direction: Direction) -> usize {
+    let facing = match direction {
+        Direction::Right => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Up => 3,
     };
+
+    ((position.y + 1) * 1000) + ((position.x + 1) * 4) + facing
+}
+
+/// The next tile towards `direction`, which is on another face of the cube past the edges.
+fn step_on_cube(
+    board: &Board,
+    cube: &Cube,
+    position: Point<usize>,
+    direction: Direction,
+) -> (Point<usize>, Direction) {
+    let step = direction.vector::<isize>();
+    let next = position
+        .x
+        .checked_add_signed(step.x)
+        .zip(position.y.checked_add_signed(step.y))
+        .map(|(x, y)| Point::new(x, y))
+        .filter(|next| board.field(*next).is_some());
+
+    match next {
+        Some(next) => (next, direction),
+        None => cube.wrap(position, direction),
+    }
+}
+
+/// Like [`move_in_2d`], but walking off the board continues on the folded cube.
+fn move_on_cube(notes: &Notes, cube: &Cube) -> usize {
+    let Notes {
+        board,
+        steps,
+        turns,
+        ..
+    } = notes;
+
+    let mut current_position = board.start();
     let mut current_direction = Direction::Right;
 
     for (i, steps) in steps.iter().enumerate() {
-        let remaining_moves = *steps;
-
-        match current_direction {
-            Direction::Right => {
-                let current_row = &rows[current_position.y];
-
-                for _ in 0..remaining_moves {
-                    let next_x = if current_position.x == current_row.end {
-                        current_row.start
-                    } else {
-                        current_position.x + 1
-                    };
-
-                    match &current_row.values[next_x - current_row.start] {
-                        Field::Floor => current_position.x = next_x,
-                        Field::Wall => break,
-                    }
-                }
-            }
-            Direction::Left => {
-                let current_row = &rows[current_position.y];
-                for _ in 0..remaining_moves {
-                    // TODO: Maybe just make add method for row and clamp at edges?
-                    let next_x = if current_position.x == current_row.start {
-                        current_row.end
-                    } else {
-                        current_position.x - 1
-                    };
-
-                    match &current_row.values[next_x - current_row.start] {
-                        Field::Floor => current_position.x = next_x,
-                        Field::Wall => break,
-                    }
-                }
-            }
-            Direction::Down => {
-                for _ in 0..remaining_moves {
-                    let mut next_y = if current_position.y == rows.len() - 1 {
-                        0
-                    } else {
-                        current_position.y + 1
-                    };
-
-                    if current_position.x < rows[next_y].start {
-                        let mut wrap_idx = 0;
-                        // TODO: We need columns if we don't want to iterate
-                        for (j, row) in rows.iter().enumerate() {
-                            if row.start <= current_position.x {
-                                wrap_idx = j;
-                                break;
-                            }
-                        }
-                        next_y = wrap_idx;
-                    } else if current_position.x > rows[next_y].end {
-                        let mut wrap_idx = 0;
-                        // TODO: We need columns if we don't want to iterate
-                        for (j, row) in rows.iter().enumerate() {
-                            if row.end >= current_position.x {
-                                wrap_idx = j;
-                                break;
-                            }
-                        }
-                        next_y = wrap_idx;
-                    }
+        for _ in 0..*steps {
+            let (next, direction) = step_on_cube(board, cube, current_position, current_direction);
 
-                    match &rows[next_y].values[current_position.x - rows[next_y].start] {
-                        Field::Floor => current_position.y = next_y,
-                        Field::Wall => break,
-                    }
-                }
-            }
-            Direction::Up => {
-                for _ in 0..remaining_moves {
-                    let mut next_y = if current_position.y == 0 {
-                        rows.len() - 1
-                    } else {
-                        current_position.y - 1
-                    };
-
-                    if current_position.x < rows[next_y].start {
-                        let mut wrap_idx = 0;
-                        // TODO: We need columns if we don't want to iterate
-                        for j in (0..rows.len()).rev() {
-                            if rows[j].start <= current_position.x {
-                                wrap_idx = j;
-                                break;
-                            }
-                        }
-                        next_y = wrap_idx;
-                    } else if current_position.x > rows[next_y].end {
-                        let mut wrap_idx = 0;
-                        // TODO: We need columns if we don't want to iterate
-                        for j in (0..rows.len()).rev() {
-                            if rows[j].end >= current_position.x {
-                                wrap_idx = j;
-                                break;
-                            }
-                        }
-                        next_y = wrap_idx;
-                    }
-
-                    match &rows[next_y].values[current_position.x - rows[next_y].start] {
-                        Field::Floor => current_position.y = next_y,
-                        Field::Wall => break,
-                    }
+            match board.field(next) {
+                Some(Field::Floor) => {
+                    current_position = next;
+                    current_direction = direction;
                 }
+                _ => break,
             }
         }
 
@@ -262,30 +196,178 @@ pub fn move_in_2d(input: &str) -> usize {
         }
     }
 
-    let mut result = ((current_position.y + 1) * 1000) + ((current_position.x + 1) * 4);
-    match current_direction {
-        Direction::Right => result += 0,
-        Direction::Down => result += 1,
-        Direction::Left => result += 2,
-        Direction::Up => result += 3,
+    password(current_position, current_direction)
+}
+
+pub struct Notes {
+    board: Board,
+    /// Any board can be walked flat, but only some fold into a cube.
+    cube: Result<Cube, Error>,
+    steps: Vec<usize>,
+    turns: Vec<Turn>,
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Parsed = Notes;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let (map, instructions) = error::split_once(input, "\n\n")?;
+
+        let board = Board::new(error::lines(map)?)?;
+        let cube = Cube::fold(board.rows());
+        let (steps, turns) = parse_instructions(instructions)?;
+
+        Ok(Notes {
+            board,
+            cube,
+            steps,
+            turns,
+        })
+    }
+
+    fn part_one(notes: &Self::Parsed) -> Answer {
+        move_in_2d(notes).into()
     }
 
-    result
+    fn part_two(notes: &Self::Parsed) -> Answer {
+        match &notes.cube {
+            Ok(cube) => {
+                crate::debug!("{cube}");
+                move_on_cube(notes, cube).into()
+            }
+            Err(err) => Answer::Failed(err.clone()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::move_in_2d;
+    use super::{cube::Cube, step_on_cube, Day22, Row};
+    use crate::{
+        solution::{Answer, Part, Solution},
+        util::geom::{Direction, Point},
+    };
+
     static INPUT_TEST: &str = include_str!("input-test.txt");
     static INPUT: &str = include_str!("input.txt");
 
     #[test]
     fn test_part_one_test_data() {
-        assert_eq!(move_in_2d(INPUT_TEST), 6032)
+        assert_eq!(
+            Day22::solve(INPUT_TEST, Part::One),
+            Ok(Answer::from(6032u64))
+        )
     }
 
     #[test]
     fn test_part_one() {
-        assert_eq!(move_in_2d(INPUT), 191010)
+        assert_eq!(Day22::solve(INPUT, Part::One), Ok(Answer::from(191010u64)))
+    }
+
+    #[test]
+    fn test_fold() {
+        for input in [INPUT_TEST, INPUT] {
+            let cube = Cube::fold(Day22::parse(input).unwrap().board.rows()).unwrap();
+
+            assert_eq!(cube.edges().count(), 24);
+            for edge in cube.edges() {
+                // Every edge leads to another face and back the same way.
+                assert_ne!(edge.face, edge.to);
+                let back = cube.edge(edge.to, edge.entering.opposite());
+                assert_eq!(back.to, edge.face);
+                assert_eq!(back.entering, edge.leaving.opposite());
+                assert_eq!(back.flipped, edge.flipped);
+            }
+        }
+
+        // In the sample, the right of face 3 is folded to the top of face 5, from its right.
+        let cube = Cube::fold(Day22::parse(INPUT_TEST).unwrap().board.rows()).unwrap();
+        let edge = cube.edge(3, Direction::Right);
+        assert_eq!((edge.to, edge.entering), (5, Direction::Down));
+        assert!(edge.flipped);
+        assert_eq!(
+            cube.wrap(Point::new(11, 5), Direction::Right),
+            (Point::new(14, 8), Direction::Down)
+        );
+    }
+
+    #[test]
+    fn test_walk_around_cube() {
+        for (input, size) in [(INPUT_TEST, 4), (INPUT, 50)] {
+            let board = Day22::parse(input).unwrap().board;
+            let rows = board.rows();
+            let cube = Cube::fold(rows).unwrap();
+
+            // Straight ahead, walls aside, every walk comes back after four faces.
+            for y in [0, size + 1, 2 * size + 2] {
+                for x in rows[y].start..=rows[y].end {
+                    for direction in Direction::ALL {
+                        let start = (Point::new(x, y), direction);
+                        let mut current = start;
+                        for _ in 0..4 * size {
+                            current = step_on_cube(&board, &cube, current.0, current.1);
+                        }
+                        assert_eq!(current, start);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_a_cube() {
+        let input = "....\n.#..\n....\n\n10R5L3";
+
+        assert_eq!(Day22::solve(input, Part::One), Ok(Answer::from(3008u64)));
+        assert_eq!(
+            Day22::solve(input, Part::Two).unwrap().to_string(),
+            "error: 12 tiles are not six square faces"
+        );
+    }
+
+    #[test]
+    fn test_row_trailing_spaces() {
+        let input = "..  \n.#  \n\n3";
+
+        assert_eq!(Day22::solve(input, Part::One), Ok(Answer::from(1008u64)));
+    }
+
+    #[test]
+    fn test_row_with_gap() {
+        let err = Day22::solve(".. .\n....\n\n3", Part::One).unwrap_err();
+
+        assert_eq!(err.message, "a row cannot have gaps");
+        assert_eq!(err.position.unwrap().column, 3);
+    }
+
+    #[test]
+    fn test_empty_row() {
+        let err = Day22::solve("..\n   \n..\n\n3", Part::One).unwrap_err();
+
+        assert_eq!(err.message, "a row needs at least one tile");
+        assert_eq!(err.position.unwrap().line, 2);
+        assert!("".parse::<Row>().is_err());
+    }
+
+    #[test]
+    fn test_blank_first_column() {
+        let input = " ..\n .#\n\n3";
+
+        assert_eq!(Day22::solve(input, Part::One), Ok(Answer::from(1012u64)));
+    }
+
+    #[test]
+    fn test_part_two_test_data() {
+        assert_eq!(
+            Day22::solve(INPUT_TEST, Part::Two),
+            Ok(Answer::from(5031u64))
+        )
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(Day22::solve(INPUT, Part::Two), Ok(Answer::from(55364u64)))
     }
 }
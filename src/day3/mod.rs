@@ -1,4 +1,7 @@
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Part, Solution, Variant},
+    util::error::{self, Error},
+};
 
 fn find_common_char(a: &str, b: &str) -> char {
     for start in a.chars() {
@@ -9,7 +12,7 @@ fn find_common_char(a: &str, b: &str) -> char {
         }
     }
 
-    panic!()
+    unreachable!("parse checks that the compartments share an item")
 }
 
 fn char_to_priority(character: char) -> u32 {
@@ -19,30 +22,21 @@ fn char_to_priority(character: char) -> u32 {
     }
 }
 
-pub fn part_one() -> u32 {
-    return INPUT
-        .lines()
-        .map(|line| line.split_at(line.len() / 2))
-        .map(|(start, end)| find_common_char(start, end))
-        .map(|common_char| char_to_priority(common_char))
-        .sum();
-}
-
 fn find_common(group: Vec<&str>) -> char {
     for character in group[0].chars() {
         if group[1].contains(character) && group[2].contains(character) {
             return character;
         }
     }
-    panic!("{:?}", group)
+    unreachable!("parse checks that every group shares an item: {:?}", group)
 }
 
-fn rucksack_beauty() -> u32 {
+fn rucksack_beauty(rucksacks: &[String]) -> u32 {
     let mut current_group_lines = 0;
     let mut groups: Vec<Vec<&str>> = Vec::with_capacity(128);
     let mut current_group: Vec<&str> = Vec::with_capacity(3);
 
-    for line in INPUT.lines() {
+    for line in rucksacks {
         current_group.push(line);
 
         current_group_lines += 1;
@@ -61,17 +55,17 @@ fn rucksack_beauty() -> u32 {
         priority_sum += char_to_priority(common_char);
     }
 
-    return priority_sum;
+    priority_sum
 }
 
 // TODO: Not faster
-fn rucksack_fast() -> u32 {
+fn rucksack_fast(rucksacks: &[String]) -> u32 {
     let mut priority_sum = 0;
 
     let mut current_group_lines = 0;
     let mut possible_tokens: Vec<char> = Vec::new();
 
-    for current_line in INPUT.lines() {
+    for current_line in rucksacks {
         if current_group_lines == 0 {
             possible_tokens = current_line.chars().collect();
             current_group_lines += 1;
@@ -102,9 +96,66 @@ fn rucksack_fast() -> u32 {
         }
     }
 
-    return priority_sum;
+    priority_sum
 }
 
-pub fn part_two() -> u32 {
-    return rucksack_beauty();
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        for line in &lines {
+            if let Some(index) = line.find(|item: char| !item.is_ascii_alphabetic()) {
+                return Err(Error::at(&line[index..], "items have to be letters"));
+            }
+
+            let (start, end) = line.split_at(line.len() / 2);
+            if !line.len().is_multiple_of(2) {
+                return Err(Error::at(line, "both compartments need as many items"));
+            }
+            if !start.chars().any(|item| end.contains(item)) {
+                return Err(Error::at(line, "the compartments have no item in common"));
+            }
+        }
+
+        if !lines.len().is_multiple_of(3) {
+            return Err(Error::at(
+                error::end_of(input),
+                "the elves have to be in groups of three",
+            ));
+        }
+        for group in lines.chunks(3) {
+            if !group[0]
+                .chars()
+                .any(|item| group[1].contains(item) && group[2].contains(item))
+            {
+                return Err(Error::at(group[0], "the group has no item in common"));
+            }
+        }
+
+        Ok(lines.into_iter().map(String::from).collect())
+    }
+
+    fn part_one(rucksacks: &Self::Parsed) -> Answer {
+        rucksacks
+            .iter()
+            .map(|line| line.split_at(line.len() / 2))
+            .map(|(start, end)| find_common_char(start, end))
+            .map(char_to_priority)
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part_two(rucksacks: &Self::Parsed) -> Answer {
+        rucksack_beauty(rucksacks).into()
+    }
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        name: "fast",
+        part: Part::Two,
+        solve: |rucksacks| rucksack_fast(rucksacks).into(),
+    }];
 }
@@ -1,45 +1,50 @@
-static INPUT: &str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        error::{self, Error},
+        interval::Interval,
+    },
+};
 
-pub fn part_one() -> usize {
-    return INPUT
-        .lines()
-        .map(|line| {
-            let (left, right) = line.split_once(',').unwrap();
-            let (left_lower, left_upper) = left.split_once('-').unwrap();
-            let (right_lower, right_upper) = right.split_once('-').unwrap();
+/// `2-4`
+fn sections(s: &str) -> Result<Interval<u32>, Error> {
+    let (lower, upper) = error::split_once(s, "-")?;
 
-            (
-                left_lower.parse::<u32>().unwrap(),
-                left_upper.parse::<u32>().unwrap(),
-                right_lower.parse::<u32>().unwrap(),
-                right_upper.parse::<u32>().unwrap(),
-            )
-        })
-        .filter(|(left_lower, left_upper, right_lower, right_upper)| {
-            (left_lower >= right_lower && left_upper <= right_upper)
-                || (right_lower >= left_lower && right_upper <= left_upper)
-        })
-        .count();
+    Ok(Interval::inclusive(
+        error::number(lower)?,
+        error::number(upper)?,
+    ))
 }
 
-pub fn part_two() -> usize {
-    return INPUT
-        .lines()
-        .map(|line| {
-            let (left, right) = line.split_once(',').unwrap();
-            let (left_lower, left_upper) = left.split_once('-').unwrap();
-            let (right_lower, right_upper) = right.split_once('-').unwrap();
+pub struct Day4;
 
-            (
-                left_lower.parse::<u32>().unwrap(),
-                left_upper.parse::<u32>().unwrap(),
-                right_lower.parse::<u32>().unwrap(),
-                right_upper.parse::<u32>().unwrap(),
-            )
-        })
-        .filter(|(left_lower, left_upper, right_lower, right_upper)| {
-            (left_upper >= right_lower && (left_upper <= right_upper))
-                || (right_upper >= left_lower && (right_upper <= left_upper))
-        })
-        .count();
+impl Solution for Day4 {
+    /// The sections of the left and right elf per pair.
+    type Parsed = Vec<(Interval<u32>, Interval<u32>)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        input
+            .lines()
+            .map(|line| {
+                let (left, right) = error::split_once(line, ",")?;
+                Ok((sections(left)?, sections(right)?))
+            })
+            .collect()
+    }
+
+    fn part_one(pairs: &Self::Parsed) -> Answer {
+        pairs
+            .iter()
+            .filter(|(left, right)| left.covers(right) || right.covers(left))
+            .count()
+            .into()
+    }
+
+    fn part_two(pairs: &Self::Parsed) -> Answer {
+        pairs
+            .iter()
+            .filter(|(left, right)| left.overlaps(right))
+            .count()
+            .into()
+    }
 }
@@ -1,13 +1,19 @@
-static INPUT: &str = include_str!("input.txt");
-
-#[derive(Debug)]
-struct Harbour {
+use crate::{
+    solution::{Answer, Solution},
+    util::{
+        error::{self, Error},
+        parse::{self, Parser},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct Harbour {
     pub stacks: Vec<Vec<char>>,
     pub multi_pick: bool,
 }
 
-#[derive(Debug)]
-struct Instruction {
+#[derive(Debug, Clone, Copy)]
+pub struct Instruction {
     pub from: usize,
     pub to: usize,
     pub amount: usize,
@@ -43,17 +49,24 @@ impl Harbour {
     }
 
     pub fn get_top_row(&self) -> String {
-        let mut row = String::with_capacity(self.stacks.capacity());
-        for stack in &self.stacks {
-            row.push(*stack.last().unwrap());
-        }
-        row
+        // Stacks that were emptied have nothing on top.
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .collect()
     }
 }
 
-fn create_harbour_from_initial_state(state: &str, enable_multi_pick: bool) -> Harbour {
+fn create_harbour_from_initial_state(
+    state: &str,
+    enable_multi_pick: bool,
+) -> Result<Harbour, Error> {
     let lines: Vec<&str> = state.lines().rev().skip(1).collect();
 
+    if lines.is_empty() {
+        return Err(Error::at(state, "expected crates above the stack numbers"));
+    }
+
     // The towers can increase above their original in height
     let stack_capacity = lines.len() * 3;
     // /4, because <space> + [ + <char> + ] per row. And +1 because <space> is missing for the first row.
@@ -65,7 +78,10 @@ fn create_harbour_from_initial_state(state: &str, enable_multi_pick: bool) -> Ha
         let chars: Vec<char> = line.chars().collect();
 
         for stack in 0..stack_amount {
-            let character = chars[1 + (stack * 4)];
+            // Editors may strip the trailing spaces of shorter stacks.
+            let Some(&character) = chars.get(1 + (stack * 4)) else {
+                break;
+            };
 
             if !character.is_whitespace() {
                 harbour.add_crate(stack, character);
@@ -73,47 +89,88 @@ fn create_harbour_from_initial_state(state: &str, enable_multi_pick: bool) -> Ha
         }
     }
 
-    harbour
+    Ok(harbour)
+}
+
+/// A stack number, which start at 1, as an index.
+fn stack(p: &mut Parser) -> Result<usize, Error> {
+    let token = p.rest();
+    match p.unsigned::<usize>()? {
+        0 => Err(Error::at(token, "stacks are numbered from 1")),
+        number => Ok(number - 1),
+    }
 }
 
-fn prepare_instructions<'a>(instructions: &'a str) -> impl Iterator<Item = Instruction> + 'a {
-    return instructions
-        .lines()
-        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
-        .map(|parts| {
-            let amount = parts[1].parse::<usize>().unwrap();
-            // TODO: Rather one more array to save the -1 here?
-            let from = parts[3].parse::<usize>().unwrap() - 1;
-            let to = parts[5].parse::<usize>().unwrap() - 1;
-
-            Instruction { from, to, amount }
-        });
+fn prepare_instructions(instructions: &str) -> Result<Vec<Instruction>, Error> {
+    parse::lines(instructions, |p| {
+        p.literal("move")?;
+        let amount = p.unsigned()?;
+        p.literal("from")?;
+        let from = stack(p)?;
+        p.literal("to")?;
+        let to = stack(p)?;
+
+        Ok(Instruction { from, to, amount })
+    })
 }
 
-pub fn part_one() -> String {
-    let (state, instructions) = INPUT.split_once("\n\n").unwrap();
+pub struct Day5;
 
-    let mut harbour = create_harbour_from_initial_state(state, false);
+impl Solution for Day5 {
+    type Parsed = (Harbour, Vec<Instruction>);
 
-    let instructions = prepare_instructions(instructions);
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        let mut blocks = parse::blocks(input);
+        let state = error::next(&mut blocks, input, "the crates")?;
+        let lines = error::next(&mut blocks, input, "the instructions")?;
 
-    for instruction in instructions {
-        harbour.apply_instruction(&instruction);
-    }
+        let harbour = create_harbour_from_initial_state(state, false)?;
+        let instructions = prepare_instructions(lines)?;
 
-    harbour.get_top_row()
-}
+        // Crates only move between stacks, so their heights are known before any part runs.
+        let stack_amount = harbour.stacks.len();
+        let mut heights: Vec<usize> = harbour.stacks.iter().map(Vec::len).collect();
+        for (line, instruction) in lines.lines().zip(&instructions) {
+            let Instruction { from, to, amount } = *instruction;
+
+            if from.max(to) >= stack_amount {
+                return Err(Error::at(
+                    line,
+                    format!("there are only {stack_amount} stacks"),
+                ));
+            }
+            if amount > heights[from] {
+                return Err(Error::at(
+                    line,
+                    format!("stack {} has fewer than {amount} crates", from + 1),
+                ));
+            }
 
-pub fn part_two() -> String {
-    let (state, instructions) = INPUT.split_once("\n\n").unwrap();
+            heights[from] -= amount;
+            heights[to] += amount;
+        }
+
+        Ok((harbour, instructions))
+    }
 
-    let mut harbour = create_harbour_from_initial_state(state, true);
+    fn part_one((harbour, instructions): &Self::Parsed) -> Answer {
+        let mut harbour = harbour.clone();
 
-    let instructions = prepare_instructions(instructions);
+        for instruction in instructions {
+            harbour.apply_instruction(instruction);
+        }
 
-    for instruction in instructions {
-        harbour.apply_instruction(&instruction);
+        harbour.get_top_row().into()
     }
 
-    harbour.get_top_row()
+    fn part_two((harbour, instructions): &Self::Parsed) -> Answer {
+        let mut harbour = harbour.clone();
+        harbour.multi_pick = true;
+
+        for instruction in instructions {
+            harbour.apply_instruction(instruction);
+        }
+
+        harbour.get_top_row().into()
+    }
 }
@@ -1,9 +1,12 @@
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Part, Solution, Variant},
+    util::Error,
+};
 
-fn find_distinct_sequence(length: usize) -> usize {
+fn find_distinct_sequence(input: &str, length: usize) -> usize {
     let mut distinct: Vec<u8> = Vec::with_capacity(length);
 
-    for (i, character) in INPUT.bytes().enumerate() {
+    for (i, character) in input.bytes().enumerate() {
         if distinct.iter().all(|d| *d != character) {
             if distinct.len() == length - 1 {
                 return i + 1;
@@ -19,10 +22,10 @@ fn find_distinct_sequence(length: usize) -> usize {
 }
 
 // This is actually way slower than the vec variant. Also with windows etc.
-fn find_distinct_sequence_binary(length: usize) -> usize {
+fn find_distinct_sequence_binary(input: &str, length: usize) -> usize {
     let converted_length: u32 = length.try_into().unwrap();
 
-    let characters = INPUT
+    let characters = input
         .bytes()
         .map(|character| character - b'a')
         .collect::<Vec<u8>>();
@@ -31,9 +34,9 @@ fn find_distinct_sequence_binary(length: usize) -> usize {
     for i in 0..characters.len() {
         dist = 0;
 
-        for j in i..(i + length) {
+        for character in characters.iter().skip(i).take(length) {
             let before = dist;
-            dist |= 1 << characters[j];
+            dist |= 1 << character;
 
             if before == dist {
                 break;
@@ -48,12 +51,40 @@ fn find_distinct_sequence_binary(length: usize) -> usize {
     panic!("Did not find {:?} distinct chars", length);
 }
 
-// 120 ys
-pub fn part_one() -> usize {
-    return find_distinct_sequence(4);
-}
+pub struct Day6;
+
+impl Solution for Day6 {
+    /// The datastream buffer.
+    type Parsed = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        match input.find(|character: char| !character.is_ascii_lowercase()) {
+            Some(index) => Err(Error::at(
+                &input[index..],
+                "the datastream has to consist of lowercase letters",
+            )),
+            None => Ok(input.to_string()),
+        }
+    }
+
+    fn part_one(datastream: &Self::Parsed) -> Answer {
+        find_distinct_sequence(datastream, 4).into()
+    }
+
+    fn part_two(datastream: &Self::Parsed) -> Answer {
+        find_distinct_sequence(datastream, 14).into()
+    }
 
-// 320 ys
-pub fn part_two() -> usize {
-    return find_distinct_sequence(14);
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            name: "binary",
+            part: Part::One,
+            solve: |datastream| find_distinct_sequence_binary(datastream, 4).into(),
+        },
+        Variant {
+            name: "binary",
+            part: Part::Two,
+            solve: |datastream| find_distinct_sequence_binary(datastream, 14).into(),
+        },
+    ];
 }
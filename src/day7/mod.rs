@@ -1,6 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Solution},
+    util::error::{self, Error},
+};
 
 #[derive(Debug)]
 struct Node {
@@ -8,13 +11,13 @@ struct Node {
     size: u64,
 }
 
-fn find_directories() -> Vec<Rc<RefCell<Node>>> {
+fn find_directories(input: &str) -> Result<Vec<Rc<RefCell<Node>>>, Error> {
     let mut directories: Vec<Rc<RefCell<Node>>> = Vec::new();
     let mut current_parent: Option<Rc<RefCell<Node>>> = None;
 
-    for line in INPUT.lines() {
+    for line in input.lines() {
         let mut parts = line.split_whitespace();
-        let first = parts.next().unwrap();
+        let first = error::next(&mut parts, line, "a command or listing")?;
 
         // Skip dir listings
         if first == "dir" {
@@ -24,19 +27,19 @@ fn find_directories() -> Vec<Rc<RefCell<Node>>> {
         // Command input
         if first == "$" {
             // Either cd <x> or ls
-            let command = parts.next().unwrap();
+            let command = error::next(&mut parts, line, "`cd` or `ls`")?;
 
             // Skip list command
             if command == "ls" {
                 continue;
             }
-            let argument = parts.next().unwrap();
+            let argument = error::next(&mut parts, line, "a directory")?;
 
             if argument == ".." {
                 // TODO: Beautify
                 current_parent = match current_parent.as_ref() {
                     Some(current_parent) => match current_parent.borrow().parent.as_ref() {
-                        Some(upper) => Some(Rc::clone(&upper)),
+                        Some(upper) => Some(Rc::clone(upper)),
                         _ => None,
                     },
                     _ => None,
@@ -45,7 +48,7 @@ fn find_directories() -> Vec<Rc<RefCell<Node>>> {
             }
 
             let parent = match current_parent.as_ref() {
-                Some(current_parent) => Some(Rc::clone(&current_parent)),
+                Some(current_parent) => Some(Rc::clone(current_parent)),
                 _ => None,
             };
 
@@ -57,10 +60,10 @@ fn find_directories() -> Vec<Rc<RefCell<Node>>> {
 
         // Is a file listing
         } else {
-            let size = first.parse::<u64>().unwrap();
+            let size = error::number::<u64>(first)?;
 
             let mut parents = match current_parent.as_ref() {
-                Some(par) => Vec::from([Rc::clone(&par)]),
+                Some(par) => Vec::from([Rc::clone(par)]),
                 _ => Vec::from([]),
             };
 
@@ -74,9 +77,8 @@ fn find_directories() -> Vec<Rc<RefCell<Node>>> {
                 let current = Rc::clone(&parents[i]);
                 current.borrow_mut().size += size;
 
-                match current.borrow().parent.as_ref() {
-                    Some(parent) => parents.push(Rc::clone(&parent)),
-                    _ => {}
+                if let Some(parent) = current.borrow().parent.as_ref() {
+                    parents.push(Rc::clone(parent))
                 }
 
                 i += 1;
@@ -84,32 +86,41 @@ fn find_directories() -> Vec<Rc<RefCell<Node>>> {
         }
     }
 
-    return directories;
+    Ok(directories)
 }
 
-pub fn part_one() -> u64 {
-    let directories = find_directories();
+pub struct Day7;
 
-    return directories
-        .iter()
-        .map(|dir| dir.borrow().size)
-        .filter(|size| *size < 100_000)
-        .sum();
-}
+impl Solution for Day7 {
+    /// Total size of every directory, the root directory comes first.
+    type Parsed = Vec<u64>;
 
-pub fn part_two() -> u64 {
-    let directories = find_directories();
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        Ok(find_directories(input)?
+            .iter()
+            .map(|dir| dir.borrow().size)
+            .collect())
+    }
 
-    let main_dir_size = directories[0].borrow().size;
-    let free_space = 70_000_000 - main_dir_size;
-    let to_free_up = 30_000_000 - free_space;
+    fn part_one(directory_sizes: &Self::Parsed) -> Answer {
+        directory_sizes
+            .iter()
+            .filter(|size| **size < 100_000)
+            .sum::<u64>()
+            .into()
+    }
 
-    let min_dir_size = directories
-        .iter()
-        .map(|dir| dir.borrow().size)
-        .filter(|size| *size > to_free_up)
-        .min()
-        .unwrap();
+    fn part_two(directory_sizes: &Self::Parsed) -> Answer {
+        let main_dir_size = directory_sizes[0];
+        let free_space = 70_000_000 - main_dir_size;
+        let to_free_up = 30_000_000 - free_space;
 
-    return min_dir_size;
+        let min_dir_size = directory_sizes
+            .iter()
+            .filter(|size| **size > to_free_up)
+            .min()
+            .unwrap();
+
+        (*min_dir_size).into()
+    }
 }
@@ -1,10 +1,11 @@
-static INPUT: &'static str = include_str!("input.txt");
+use crate::{
+    solution::{Answer, Part, Solution, Variant},
+    util::{grid::Grid, Error},
+};
 
-fn count_visible_trees(input: &str) -> u64 {
-    let lines: Vec<_> = input.lines().map(|line| line.trim().as_bytes()).collect();
-
-    let height = lines.len();
-    let width = lines[0].len();
+fn count_visible_trees(trees: &Grid<u8>) -> u64 {
+    let height = trees.height();
+    let width = trees.width();
 
     let mut visible: u64 = ((width + height) * 2) as u64 - 4;
 
@@ -13,16 +14,16 @@ fn count_visible_trees(input: &str) -> u64 {
 
     let mut north_boundary = vec![0; width];
 
-    let mut north_max = Vec::from(lines[0]);
-    let mut south_max = Vec::from(lines[height - 1]);
+    let mut north_max = Vec::from(trees.row(0));
+    let mut south_max = Vec::from(trees.row(height - 1));
 
     for y in 1..(height - 1) {
         // West
-        let mut west_max = lines[y][0];
+        let mut west_max = trees[(0, y)];
         for x in 1..(width - 1) {
-            if lines[y][x] > west_max {
+            if trees[(x, y)] > west_max {
                 visible += 1;
-                west_max = lines[y][x];
+                west_max = trees[(x, y)];
                 left_boundary[y][west_max as usize] = x;
 
                 // Early abort
@@ -33,11 +34,11 @@ fn count_visible_trees(input: &str) -> u64 {
         }
 
         // East
-        let mut east_max = lines[y][width - 1];
+        let mut east_max = trees[(width - 1, y)];
         for x in ((left_boundary[y][west_max as usize] + 1)..=(width - 2)).rev() {
-            if lines[y][x] > east_max {
+            if trees[(x, y)] > east_max {
                 visible += 1;
-                east_max = lines[y][x];
+                east_max = trees[(x, y)];
                 right_boundary[y][east_max as usize] = x;
 
                 // No need to search further left if we are at the row maximum already.
@@ -49,8 +50,8 @@ fn count_visible_trees(input: &str) -> u64 {
 
         // Top -> Bottom
         for x in 1..(width - 1) {
-            if lines[y][x] > north_max[x] {
-                north_max[x] = lines[y][x];
+            if trees[(x, y)] > north_max[x] {
+                north_max[x] = trees[(x, y)];
                 // Boundary for lower
                 north_boundary[x] = y;
 
@@ -67,8 +68,8 @@ fn count_visible_trees(input: &str) -> u64 {
     // Bottom -> Top
     for y in (1..(height - 1)).rev() {
         for x in 1..(width - 1) {
-            if north_boundary[x] < y && lines[y][x] > south_max[x] {
-                south_max[x] = lines[y][x];
+            if north_boundary[x] < y && trees[(x, y)] > south_max[x] {
+                south_max[x] = trees[(x, y)];
 
                 // To make sure we don't count trees multiple times.
                 if x > left_boundary[y][south_max[x] as usize]
@@ -80,29 +81,27 @@ fn count_visible_trees(input: &str) -> u64 {
         }
     }
 
-    return visible;
+    visible
 }
 
-fn count_visible_trees_simple(input: &str) -> u64 {
-    let lines: Vec<_> = input.lines().map(|line| line.as_bytes()).collect();
-
-    let height = lines.len();
-    let width = lines[0].len();
+fn count_visible_trees_simple(trees: &Grid<u8>) -> u64 {
+    let height = trees.height();
+    let width = trees.width();
 
     let mut visible: u64 = ((width + height) * 2) as u64 - 4;
 
-    let mut seen: Vec<Vec<bool>> = vec![vec![false; width]; height];
+    let mut seen = Grid::new(width, height, false);
 
-    let mut north_max = Vec::from(lines[0]);
+    let mut north_max = Vec::from(trees.row(0));
 
     for y in 1..(height - 1) {
         // West
-        let mut west_max = lines[y][0];
+        let mut west_max = trees[(0, y)];
         for x in 1..(width - 1) {
-            if lines[y][x] > west_max {
+            if trees[(x, y)] > west_max {
                 visible += 1;
-                west_max = lines[y][x];
-                seen[y][x] = true;
+                west_max = trees[(x, y)];
+                seen[(x, y)] = true;
 
                 // Early abort
                 if west_max == 9 {
@@ -112,14 +111,14 @@ fn count_visible_trees_simple(input: &str) -> u64 {
         }
 
         // East
-        let mut east_max = lines[y][width - 1];
+        let mut east_max = trees[(width - 1, y)];
         for x in (1..=(width - 2)).rev() {
-            if lines[y][x] > east_max {
-                east_max = lines[y][x];
+            if trees[(x, y)] > east_max {
+                east_max = trees[(x, y)];
 
-                if seen[y][x] == false {
+                if !seen[(x, y)] {
                     visible += 1;
-                    seen[y][x] = true;
+                    seen[(x, y)] = true;
                 }
 
                 // No need to search further left if we are at the row maximum already.
@@ -131,53 +130,51 @@ fn count_visible_trees_simple(input: &str) -> u64 {
 
         // Top -> Bottom
         for x in 1..(width - 1) {
-            if lines[y][x] > north_max[x] {
-                north_max[x] = lines[y][x];
+            if trees[(x, y)] > north_max[x] {
+                north_max[x] = trees[(x, y)];
 
-                if seen[y][x] == false {
+                if !seen[(x, y)] {
                     visible += 1;
-                    seen[y][x] = true;
+                    seen[(x, y)] = true;
                 }
             }
         }
     }
 
     // Bottom -> Top
-    let mut south_max: Vec<u8> = Vec::from(lines[height - 1]);
+    let mut south_max: Vec<u8> = Vec::from(trees.row(height - 1));
     for y in (1..(height - 1)).rev() {
         for x in 1..(width - 1) {
-            if lines[y][x] > south_max[x] {
-                south_max[x] = lines[y][x];
+            if trees[(x, y)] > south_max[x] {
+                south_max[x] = trees[(x, y)];
 
-                if seen[y][x] == false {
+                if !seen[(x, y)] {
                     visible += 1;
-                    seen[y][x] = true;
+                    seen[(x, y)] = true;
                 }
             }
         }
     }
 
-    return visible;
+    visible
 }
 
-fn count_visible_trees_vertical_vec(input: &str) -> u64 {
-    let lines: Vec<_> = input.lines().map(|line| line.trim().as_bytes()).collect();
-
-    let height = lines.len();
-    let width = lines[0].len();
+fn count_visible_trees_vertical_vec(trees: &Grid<u8>) -> u64 {
+    let height = trees.height();
+    let width = trees.width();
 
     let mut visible: u64 = ((width + height) * 2) as u64 - 4;
 
-    let mut seen: Vec<Vec<bool>> = vec![vec![false; width]; height];
+    let mut seen = Grid::new(width, height, false);
 
     let mut vertical: Vec<Vec<u8>> = vec![Vec::with_capacity(height); width];
 
-    let mut north_max = Vec::from(lines[0]);
-    let mut south_max: Vec<u8> = Vec::from(lines[height - 1]);
+    let mut north_max = Vec::from(trees.row(0));
+    let mut south_max: Vec<u8> = Vec::from(trees.row(height - 1));
 
     for y in 0..=(height - 1) {
         for x in 0..width {
-            vertical[x].push(lines[y][x]);
+            vertical[x].push(trees[(x, y)]);
         }
 
         if y == 0 || y == height - 1 {
@@ -185,12 +182,12 @@ fn count_visible_trees_vertical_vec(input: &str) -> u64 {
         }
 
         // West
-        let mut west_max = lines[y][0];
+        let mut west_max = trees[(0, y)];
         for x in 1..(width - 1) {
-            if lines[y][x] > west_max {
+            if trees[(x, y)] > west_max {
                 visible += 1;
-                west_max = lines[y][x];
-                seen[y][x] = true;
+                west_max = trees[(x, y)];
+                seen[(x, y)] = true;
 
                 // Early abort
                 if west_max == 9 {
@@ -200,13 +197,15 @@ fn count_visible_trees_vertical_vec(input: &str) -> u64 {
         }
 
         // East
-        let mut east_max = lines[y][width - 1];
+        let mut east_max = trees[(width - 1, y)];
         for x in (1..=(width - 2)).rev() {
-            if lines[y][x] > east_max {
-                // TODO: Not if already seen because we are at left max
-                visible += 1;
-                east_max = lines[y][x];
-                seen[y][x] = true;
+            if trees[(x, y)] > east_max {
+                // The row maximum may have been seen from the west already.
+                if !seen[(x, y)] {
+                    visible += 1;
+                    seen[(x, y)] = true;
+                }
+                east_max = trees[(x, y)];
 
                 // No need to search further left if we are at the row maximum already.
                 if east_max == west_max {
@@ -221,9 +220,9 @@ fn count_visible_trees_vertical_vec(input: &str) -> u64 {
             if vertical[x][y] > north_max[x] {
                 north_max[x] = vertical[x][y];
 
-                if seen[y][x] == false {
+                if !seen[(x, y)] {
                     visible += 1;
-                    seen[y][x] = true;
+                    seen[(x, y)] = true;
                 }
 
                 // Early abort
@@ -237,39 +236,37 @@ fn count_visible_trees_vertical_vec(input: &str) -> u64 {
             if vertical[x][y] > south_max[x] {
                 south_max[x] = vertical[x][y];
 
-                if seen[y][x] == false {
+                if !seen[(x, y)] {
                     visible += 1;
-                    seen[y][x] = true;
+                    seen[(x, y)] = true;
                 }
 
                 // Early abort
-                if south_max == north_max {
+                if south_max[x] == north_max[x] {
                     break;
                 }
             }
         }
     }
 
-    return visible;
+    visible
 }
 
-fn visible_trees_from_inside(input: &str) -> u64 {
-    let lines: Vec<_> = input.lines().map(|line| line.trim().as_bytes()).collect();
-
-    let height = lines.len();
-    let width = lines[0].len();
+fn visible_trees_from_inside(trees: &Grid<u8>) -> u64 {
+    let height = trees.height();
+    let width = trees.width();
 
     let mut max = 0;
 
     for y in 0..=(height - 1) {
         for x in 0..=(width - 1) {
-            let own_height = lines[y][x];
+            let own_height = trees[(x, y)];
 
             let mut seen_west = 0;
             for w in (0..x).rev() {
                 seen_west += 1;
 
-                if lines[y][w] >= own_height {
+                if trees[(w, y)] >= own_height {
                     break;
                 }
             }
@@ -278,7 +275,7 @@ fn visible_trees_from_inside(input: &str) -> u64 {
             for w in (x + 1)..=(width - 1) {
                 seen_east += 1;
 
-                if lines[y][w] >= own_height {
+                if trees[(w, y)] >= own_height {
                     break;
                 }
             }
@@ -286,7 +283,7 @@ fn visible_trees_from_inside(input: &str) -> u64 {
             let mut seen_north = 0;
             for w in (0..y).rev() {
                 seen_north += 1;
-                if lines[w][x] >= own_height {
+                if trees[(x, w)] >= own_height {
                     break;
                 }
             }
@@ -294,7 +291,7 @@ fn visible_trees_from_inside(input: &str) -> u64 {
             let mut seen_south = 0;
             for w in (y + 1)..=(height - 1) {
                 seen_south += 1;
-                if lines[w][x] >= own_height {
+                if trees[(x, w)] >= own_height {
                     break;
                 }
             }
@@ -306,26 +303,24 @@ fn visible_trees_from_inside(input: &str) -> u64 {
         }
     }
 
-    return max;
+    max
 }
 
-fn visible_trees_from_inside_one_way_caching(input: &str) -> usize {
-    let lines: Vec<_> = input.lines().map(|line| line.trim().as_bytes()).collect();
+fn visible_trees_from_inside_one_way_caching(trees: &Grid<u8>) -> usize {
+    let height = trees.height();
+    let width = trees.width();
 
-    let height = lines.len();
-    let width = lines[0].len();
-
-    let mut west_cache: Vec<Vec<usize>> = vec![vec![1; width]; height];
-    let mut north_cache: Vec<Vec<usize>> = vec![vec![1; width]; height];
+    let mut west_cache = Grid::new(width, height, 1);
+    let mut north_cache = Grid::new(width, height, 1);
 
     let mut max = 0;
 
     for y in 0..=(height - 1) {
         for x in 0..=(width - 1) {
-            let own_height = lines[y][x];
+            let own_height = trees[(x, y)];
 
-            let (mut seen_west, search_from) = if x != 0 && own_height > lines[y][x - 1] {
-                (west_cache[y][x - 1], x - west_cache[y][x - 1])
+            let (mut seen_west, search_from) = if x != 0 && own_height > trees[(x - 1, y)] {
+                (west_cache[(x - 1, y)], x - west_cache[(x - 1, y)])
             } else {
                 (0, x)
             };
@@ -333,39 +328,39 @@ fn visible_trees_from_inside_one_way_caching(input: &str) -> usize {
             for w in (0..search_from).rev() {
                 seen_west += 1;
 
-                if lines[y][w] >= own_height {
+                if trees[(w, y)] >= own_height {
                     break;
                 }
             }
-            west_cache[y][x] = seen_west;
+            west_cache[(x, y)] = seen_west;
 
             let mut seen_east = 0;
             for w in (x + 1)..=(width - 1) {
                 seen_east += 1;
 
-                if lines[y][w] >= own_height {
+                if trees[(w, y)] >= own_height {
                     break;
                 }
             }
 
-            let (mut seen_north, search_from) = if y != 0 && own_height > lines[y - 1][x] {
-                (north_cache[y - 1][x], y - north_cache[y - 1][x])
+            let (mut seen_north, search_from) = if y != 0 && own_height > trees[(x, y - 1)] {
+                (north_cache[(x, y - 1)], y - north_cache[(x, y - 1)])
             } else {
                 (0, y)
             };
 
             for w in (0..search_from).rev() {
                 seen_north += 1;
-                if lines[w][x] >= own_height {
+                if trees[(x, w)] >= own_height {
                     break;
                 }
             }
-            north_cache[y][x] = seen_north;
+            north_cache[(x, y)] = seen_north;
 
             let mut seen_south = 0;
             for w in (y + 1)..=(height - 1) {
                 seen_south += 1;
-                if lines[w][x] >= own_height {
+                if trees[(x, w)] >= own_height {
                     break;
                 }
             }
@@ -377,31 +372,63 @@ fn visible_trees_from_inside_one_way_caching(input: &str) -> usize {
         }
     }
 
-    return max;
+    max
 }
 
-pub fn part_one() -> u64 {
-    // ~ 1.35ms Created with false premises
-    // return count_visible_trees(INPUT);
-    // ~ 2ms Not as efficient
-    // return count_visible_trees_vertical_vec(INPUT);
-    // ~ 1.3ms
-    return count_visible_trees_simple(INPUT);
-}
+pub struct Day8;
 
-pub fn part_two() {
-    // ~ 3.3ms
-    visible_trees_from_inside(INPUT);
-    // ~4.6ms Is not worth the optimization
-    // visible_trees_from_inside_one_way_caching(INPUT);
+impl Solution for Day8 {
+    /// The height of every tree, from 0 to 9.
+    type Parsed = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        Grid::parse(input, |tree| tree.to_digit(10).map(|height| height as u8))
+    }
+
+    fn part_one(map: &Self::Parsed) -> Answer {
+        count_visible_trees_simple(map).into()
+    }
+
+    fn part_two(map: &Self::Parsed) -> Answer {
+        visible_trees_from_inside(map).into()
+    }
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        // Created with false premises
+        Variant {
+            name: "boundaries",
+            part: Part::One,
+            solve: |map| count_visible_trees(map).into(),
+        },
+        // Not as efficient
+        Variant {
+            name: "vertical-vec",
+            part: Part::One,
+            solve: |map| count_visible_trees_vertical_vec(map).into(),
+        },
+        // Is not worth the optimization
+        Variant {
+            name: "one-way-caching",
+            part: Part::Two,
+            solve: |map| visible_trees_from_inside_one_way_caching(map).into(),
+        },
+    ];
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day8::count_visible_trees;
     use crate::day8::count_visible_trees_simple;
+    use crate::day8::count_visible_trees_vertical_vec;
+    use crate::{solution::Solution, util::grid::Grid};
 
-    use super::visible_trees_from_inside;
+    use super::{visible_trees_from_inside, Day8};
+
+    /// The maps below are indented with the code.
+    fn trees(input: &str) -> Grid<u8> {
+        let lines: Vec<_> = input.lines().map(str::trim).collect();
+        Day8::parse(&lines.join("\n")).unwrap()
+    }
 
     static TEST_INPUT: &str = r"302373
     253512
@@ -409,8 +436,9 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(count_visible_trees(TEST_INPUT), 17);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT), 17);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT)), 17);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT)), 17);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT)), 17);
     }
 
     static TEST_INPUT_2: &str = r"12345
@@ -419,8 +447,9 @@ mod tests {
 
     #[test]
     fn test2() {
-        assert_eq!(count_visible_trees(TEST_INPUT_2), 14);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_2), 14);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_2)), 14);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_2)), 14);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_2)), 14);
     }
 
     static TEST_INPUT_3: &str = r"12345
@@ -430,8 +459,9 @@ mod tests {
 
     #[test]
     fn test3() {
-        assert_eq!(count_visible_trees(TEST_INPUT_3), 20);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_3), 20);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_3)), 20);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_3)), 20);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_3)), 20);
     }
 
     static TEST_INPUT_4: &str = r"30373
@@ -442,8 +472,9 @@ mod tests {
 
     #[test]
     fn test4() {
-        assert_eq!(count_visible_trees(TEST_INPUT_4), 21);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_4), 21);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_4)), 21);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_4)), 21);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_4)), 21);
     }
 
     static TEST_INPUT_5: &str = r"30373
@@ -454,8 +485,9 @@ mod tests {
 
     #[test]
     fn test5() {
-        assert_eq!(count_visible_trees(TEST_INPUT_5), 22);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_5), 22);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_5)), 22);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_5)), 22);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_5)), 22);
     }
 
     static TEST_INPUT_6: &str = r"20102
@@ -464,8 +496,9 @@ mod tests {
 
     #[test]
     fn test6() {
-        assert_eq!(count_visible_trees(TEST_INPUT_6), 14);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_6), 14);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_6)), 14);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_6)), 14);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_6)), 14);
     }
 
     static TEST_INPUT_7: &str = r"20112
@@ -474,8 +507,9 @@ mod tests {
 
     #[test]
     fn test7() {
-        assert_eq!(count_visible_trees(TEST_INPUT_7), 14);
-        assert_eq!(count_visible_trees_simple(TEST_INPUT_7), 14);
+        assert_eq!(count_visible_trees(&trees(TEST_INPUT_7)), 14);
+        assert_eq!(count_visible_trees_simple(&trees(TEST_INPUT_7)), 14);
+        assert_eq!(count_visible_trees_vertical_vec(&trees(TEST_INPUT_7)), 14);
     }
 
     static TEST_INPUT_8: &str = r"30373
@@ -486,6 +520,6 @@ mod tests {
 
     #[test]
     fn test8() {
-        assert_eq!(visible_trees_from_inside(TEST_INPUT_8), 8);
+        assert_eq!(visible_trees_from_inside(&trees(TEST_INPUT_8)), 8);
     }
 }
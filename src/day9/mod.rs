@@ -1,40 +1,37 @@
-static INPUT: &'static str = include_str!("input.txt");
-
-// ~1.8ms
-pub fn part_one() -> u64 {
-    let lines: Vec<_> = INPUT
-        .lines()
-        .map(|line| line.split_once(' ').unwrap())
-        .map(|(dir, steps)| (dir, steps.parse::<u8>().unwrap()))
-        .collect();
-
-    let grid_width = 600;
-    let grid_height = 600;
+use crate::{
+    solution::{self, Answer, Solution, Variant},
+    util::{
+        error::{self, Error},
+        sparse::SparseGrid,
+    },
+};
+
+fn count_tail_visits(lines: &[(char, u8)]) -> u64 {
     let mut visited_distinct = 1;
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; grid_width]; grid_height];
+    let mut visited: SparseGrid<bool> = SparseGrid::new();
 
-    let mut head_x = grid_width / 2;
-    let mut head_y = grid_height / 2;
+    let mut head_x: i64 = 0;
+    let mut head_y: i64 = 0;
 
     let mut tail_x = head_x;
     let mut tail_y = head_y;
 
-    visited[tail_x][tail_y] = true;
+    visited.set(tail_x, tail_y, true);
 
-    for (dir, steps) in lines {
+    for (dir, steps) in lines.iter().copied() {
         for _ in 0..steps {
             match dir {
-                "L" => head_x -= 1,
-                "R" => head_x += 1,
-                "U" => head_y -= 1,
-                "D" => head_y += 1,
+                'L' => head_x -= 1,
+                'R' => head_x += 1,
+                'U' => head_y -= 1,
+                'D' => head_y += 1,
                 _ => panic!("Unexpected direction"),
             }
 
-            let diff_x: i32 = head_x as i32 - tail_x as i32;
-            let diff_y: i32 = head_y as i32 - tail_y as i32;
+            let diff_x = head_x - tail_x;
+            let diff_y = head_y - tail_y;
 
-            if diff_x > 1 || diff_x < -1 || diff_y > 1 || diff_y < -1 {
+            if !(-1..=1).contains(&diff_x) || !(-1..=1).contains(&diff_y) {
                 if diff_x > 0 {
                     tail_x += 1;
                 } else if diff_x < 0 {
@@ -47,39 +44,29 @@ pub fn part_one() -> u64 {
                     tail_y -= 1;
                 }
 
-                if visited[tail_x][tail_y] == false {
-                    visited[tail_x][tail_y] = true;
+                if !visited.set(tail_x, tail_y, true) {
                     visited_distinct += 1;
                 }
             }
         }
     }
 
-    return visited_distinct;
+    visited_distinct
 }
 
 #[derive(Clone)]
 struct Part {
-    x: usize,
-    y: usize,
+    x: i64,
+    y: i64,
 }
 
 // Idea: every part before the tail is itself a tail of the next part.
-// ~4.2ms
-pub fn part_two() -> u64 {
-    let lines: Vec<_> = INPUT
-        .lines()
-        .map(|line| line.split_once(' ').unwrap())
-        .map(|(dir, steps)| (dir, steps.parse::<u8>().unwrap()))
-        .collect();
-
-    let grid_width = 600;
-    let grid_height = 600;
+fn count_knot_tail_visits(lines: &[(char, u8)]) -> u64 {
     let mut visited_distinct = 1;
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; grid_width]; grid_height];
+    let mut visited: SparseGrid<bool> = SparseGrid::new();
 
-    let mut head_x = grid_width / 2;
-    let mut head_y = grid_height / 2;
+    let mut head_x: i64 = 0;
+    let mut head_y: i64 = 0;
 
     let mut parts: Vec<Part> = vec![
         Part {
@@ -89,23 +76,23 @@ pub fn part_two() -> u64 {
         9
     ];
 
-    visited[head_x][head_y] = true;
+    visited.set(head_x, head_y, true);
 
-    for (dir, steps) in lines {
+    for (dir, steps) in lines.iter().copied() {
         for _ in 0..steps {
             match dir {
-                "L" => head_x -= 1,
-                "R" => head_x += 1,
-                "U" => head_y -= 1,
-                "D" => head_y += 1,
+                'L' => head_x -= 1,
+                'R' => head_x += 1,
+                'U' => head_y -= 1,
+                'D' => head_y += 1,
                 _ => panic!("Unexpected direction"),
             }
 
             let mut previous = (head_x, head_y);
 
             for part in &mut parts {
-                let diff_x: i32 = previous.0 as i32 - part.x as i32;
-                let diff_y: i32 = previous.1 as i32 - part.y as i32;
+                let diff_x = previous.0 - part.x;
+                let diff_y = previous.1 - part.y;
 
                 if diff_x == 2 || diff_x == -2 || diff_y == 2 || diff_y == -2 {
                     if diff_x > 0 {
@@ -125,71 +112,92 @@ pub fn part_two() -> u64 {
             }
 
             let tail = &parts[parts.len() - 1];
-            if visited[tail.x][tail.y] == false {
-                visited[tail.x][tail.y] = true;
+            if !visited.set(tail.x, tail.y, true) {
                 visited_distinct += 1;
             }
         }
     }
 
-    println!("{:?}", visited_distinct);
-
-    return visited_distinct;
+    visited_distinct
 }
 
-fn distinct_visit(length: usize) -> u64 {
-    let lines: Vec<_> = INPUT
-        .lines()
-        .map(|line| line.split_once(' ').unwrap())
-        .map(|(dir, steps)| (dir, steps.parse::<u8>().unwrap()))
-        .collect();
-
-    let grid_width = 600;
-    let grid_height = 600;
-    let mut visited_distinct = 1;
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; grid_width]; grid_height];
+fn distinct_visit(lines: &[(char, u8)], length: usize) -> u64 {
+    let mut visited: SparseGrid<bool> = SparseGrid::new();
 
-    let mut parts: Vec<Part> = vec![
-        Part {
-            x: grid_width / 2,
-            y: grid_height / 2
-        };
-        length
-    ];
+    let mut parts: Vec<Part> = vec![Part { x: 0, y: 0 }; length];
 
-    visited[parts[0].x as usize][parts[0].y as usize] = true;
+    visited.set(0, 0, true);
 
-    for (dir, steps) in lines {
+    for (dir, steps) in lines.iter().copied() {
         for _ in 0..steps {
             match dir {
-                "L" => parts[0].x -= 1,
-                "R" => parts[0].x += 1,
-                "U" => parts[0].y -= 1,
-                "D" => parts[0].y += 1,
+                'L' => parts[0].x -= 1,
+                'R' => parts[0].x += 1,
+                'U' => parts[0].y -= 1,
+                'D' => parts[0].y += 1,
                 _ => unreachable!(),
             }
 
             for i in 1..parts.len() {
-                let diff_x: i32 = parts[i - 1].x as i32 - parts[i].x as i32;
-                let diff_y: i32 = parts[i - 1].y as i32 - parts[i].y as i32;
+                let diff_x = parts[i - 1].x - parts[i].x;
+                let diff_y = parts[i - 1].y - parts[i].y;
 
                 if diff_x.abs() == 2 || diff_y.abs() == 2 {
-                    parts[i].x = (parts[i].x as i32 + diff_x.signum()) as usize;
-                    parts[i].y = (parts[i].y as i32 + diff_y.signum()) as usize;
+                    parts[i].x += diff_x.signum();
+                    parts[i].y += diff_y.signum();
                 }
             }
 
             let tail = &parts[parts.len() - 1];
-            if visited[tail.x][tail.y] == false {
-                visited[tail.x][tail.y] = true;
-                visited_distinct += 1;
-            }
+            visited.set(tail.x, tail.y, true);
         }
     }
 
-    println!("{:?}", visited_distinct);
-
-    return visited_distinct;
+    visited.count() as u64
 }
 // TODO: Beautiful version
 // TODO: measurement sin release
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    /// Direction and amount of steps of every head move.
+    type Parsed = Vec<(char, u8)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error> {
+        input
+            .lines()
+            .map(|line| {
+                let (dir, steps) = error::split_once(line, " ")?;
+
+                match dir {
+                    "L" | "R" | "U" | "D" => {
+                        Ok((dir.chars().next().unwrap(), error::number(steps)?))
+                    }
+                    _ => Err(Error::at(dir, format!("unknown direction `{dir}`"))),
+                }
+            })
+            .collect()
+    }
+
+    fn part_one(moves: &Self::Parsed) -> Answer {
+        count_tail_visits(moves).into()
+    }
+
+    fn part_two(moves: &Self::Parsed) -> Answer {
+        count_knot_tail_visits(moves).into()
+    }
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            name: "generic",
+            part: solution::Part::One,
+            solve: |moves| distinct_visit(moves, 2).into(),
+        },
+        Variant {
+            name: "generic",
+            part: solution::Part::Two,
+            solve: |moves| distinct_visit(moves, 10).into(),
+        },
+    ];
+}
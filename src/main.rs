@@ -6,7 +6,13 @@ pub mod day13;
 pub mod day14;
 pub mod day15;
 pub mod day16;
+pub mod day17;
+pub mod day18;
 pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
 pub mod day3;
 pub mod day4;
 pub mod day5;
@@ -14,74 +20,22 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod runner;
+pub mod solution;
 pub mod util;
 
-fn execute_days() {
-    // println!("## Day 1");
-    // util::with_timing(day1::part_one);
-    // util::with_timing(day1::part_two);
-
-    // println!("## Day 2");
-    // util::with_timing(day2::part_one);
-    // util::with_timing(day2::part_two);
-
-    // println!("## Day 3");
-    // util::with_timing(day3::part_one);
-    // util::with_timing(day3::part_two);
-
-    // println!("## Day 4");
-    // util::with_timing(day4::part_one);
-    // util::with_timing(day4::part_two);
-
-    // println!("## Day 5");
-    // util::with_timing(day5::part_one);
-    // util::with_timing(day5::part_two);
-
-    // println!("## Day 6");
-    // util::with_timing(day6::part_one);
-    // util::with_timing(day6::part_two);
-
-    // println!("## Day 7");
-    // util::with_timing(day7::part_one);
-    // util::with_timing(day7::part_two);
-
-    // println!("## Day 8");
-    // util::with_timing(day8::part_one);
-    // util::with_timing(day8::part_two);
-
-    // println!("## Day 9");
-    // util::with_timing(day9::part_one);
-    // util::with_timing(day9::part_two);
-
-    // println!("## Day 10");
-    // util::with_timing(day10::part_one);
-    // util::with_timing(day10::part_two);
-
-    // println!("## Day 11");
-    // util::with_timing(day11::part_one);
-    // // util::with_timing(day11::part_two);
-
-    // // println!("## Day 12");
-    // util::with_timing(day12::part_one);
-    // util::with_timing(day12::part_two);
-
-    // println!("## Day 13");
-    // util::with_timing(day13::part_one);
-    // // util::with_timing(day13::part_two);
-
-    // println!("## Day 14");
-    // util::with_timing(day14::part_one);
-    // util::with_timing(day14::part_two);
-
-    // println!("## Day 15");
-    // util::with_timing(day15::part_one);
-    // util::with_timing(day15::part_two);
-
-    println!("## Day 16");
-    util::with_timing(day16::part_one);
-    // util::with_timing(day16::part_two);
-}
-
 fn main() {
-    util::with_timing(execute_days);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match runner::Options::from_args(&args) {
+        Ok(options) => {
+            if !runner::run(&options) {
+                std::process::exit(1);
+            }
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{}", runner::USAGE);
+            std::process::exit(2);
+        }
+    }
 }
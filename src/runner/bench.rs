@@ -0,0 +1,419 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::Options;
+use crate::{
+    solution::{self, Answer},
+    util,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub runs: usize,
+    pub warmup: usize,
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            runs: 50,
+            warmup: 5,
+            save: None,
+            baseline: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is required");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        let nanos: Vec<f64> = sorted
+            .iter()
+            .map(|sample| sample.as_nanos() as f64)
+            .collect();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        // Sample standard deviation, a single run has none.
+        let variance = match nanos.len() {
+            1 => 0.0,
+            len => nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / (len - 1) as f64,
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+/// What was measured of a day, `parse` or the number of a part with an optional `/variant`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub day: u8,
+    pub stage: String,
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.day, self.stage)
+    }
+}
+
+/**
+ * One measurement per line, durations in nanoseconds:
+ *
+ * ```text
+ * 14 parse 88012 90110 91523 3012
+ * 14 1 3410211 3502877 3533190 80111
+ * ```
+ */
+pub fn write_baseline(results: &[(Key, Stats)]) -> String {
+    results
+        .iter()
+        .map(|(key, stats)| {
+            format!(
+                "{key} {} {} {} {}\n",
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos()
+            )
+        })
+        .collect()
+}
+
+pub fn read_baseline(content: &str) -> Result<HashMap<Key, Stats>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let invalid = || format!("line {}: invalid baseline entry `{line}`", i + 1);
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [day, stage, min, median, mean, stddev] = parts[..] else {
+                return Err(invalid());
+            };
+
+            let nanos = |value: &str| {
+                value
+                    .parse()
+                    .map(Duration::from_nanos)
+                    .map_err(|_| invalid())
+            };
+            let key = Key {
+                day: day.parse().map_err(|_| invalid())?,
+                stage: stage.to_string(),
+            };
+            let stats = Stats {
+                min: nanos(min)?,
+                median: nanos(median)?,
+                mean: nanos(mean)?,
+                stddev: nanos(stddev)?,
+            };
+
+            Ok((key, stats))
+        })
+        .collect()
+}
+
+fn load_baseline(path: &Path) -> Result<HashMap<Key, Stats>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("could not read baseline {}: {err}", path.display()))?;
+
+    read_baseline(&content).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/**
+ * Runs `f` `warmup` times without measuring, then `runs` times. The results go through
+ * [`black_box`], so the work cannot be optimized away.
+ */
+fn sample<T>(settings: &Settings, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    for _ in 0..settings.warmup {
+        black_box(f());
+    }
+
+    (0..settings.runs.max(1))
+        .map(|_| util::measure(|| black_box(f())).1)
+        .collect()
+}
+
+fn print_row(label: &str, stats: &Stats, baseline: Option<&Stats>) {
+    let change = match baseline {
+        None => String::new(),
+        Some(baseline) => {
+            let before = baseline.median.as_nanos() as f64;
+            let after = stats.median.as_nanos() as f64;
+            format!("  {:+.1}%", (after - before) / before.max(1.0) * 100.0)
+        }
+    };
+
+    println!(
+        "{label:<8}{:>12.2?}{:>12.2?}{:>12.2?}{:>12.2?}{change}",
+        stats.min, stats.median, stats.mean, stats.stddev
+    );
+}
+
+pub fn bench(options: &Options, settings: &Settings) {
+    let baseline = match settings.baseline.as_deref().map(load_baseline) {
+        None => HashMap::new(),
+        Some(Ok(baseline)) => baseline,
+        Some(Err(message)) => {
+            eprintln!("error: {message}");
+            return;
+        }
+    };
+
+    let mut results: Vec<(Key, Stats)> = Vec::new();
+
+    for number in &options.days {
+        println!("## Day {number}");
+
+        let input = match options.input.load(*number) {
+            Ok(input) => input,
+            Err(message) => {
+                eprintln!("error: {message}");
+                continue;
+            }
+        };
+
+        let day = solution::find(*number).unwrap();
+        let parsed = match day.parse(&input) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                eprintln!("error: {err}");
+                continue;
+            }
+        };
+
+        println!(
+            "{:<8}{:>12}{:>12}{:>12}{:>12}",
+            "", "min", "median", "mean", "stddev"
+        );
+
+        let mut record = |stage: &str, label: &str, samples: &[Duration]| {
+            let key = Key {
+                day: *number,
+                stage: stage.to_string(),
+            };
+            let stats = Stats::from_samples(samples);
+
+            print_row(label, &stats, baseline.get(&key));
+            results.push((key, stats));
+        };
+
+        record("parse", "Parse", &sample(settings, || day.parse(&input)));
+
+        for part in &options.parts {
+            let parsed = parsed.as_ref();
+            let mut stage = part.number().to_string();
+            let samples = match &options.variant {
+                None if day.solve(parsed, *part) == Answer::Unsolved => {
+                    println!("Part {}: unsolved", part.number());
+                    continue;
+                }
+                None => sample(settings, || day.solve(parsed, *part)),
+                Some(name) if day.solve_variant(parsed, name, *part).is_some() => {
+                    stage = format!("{stage}/{name}");
+                    sample(settings, || day.solve_variant(parsed, name, *part))
+                }
+                Some(name) => {
+                    println!("Part {}: no variant `{name}`", part.number());
+                    continue;
+                }
+            };
+
+            record(&stage, &format!("Part {}", part.number()), &samples);
+        }
+    }
+
+    if let Some(path) = &settings.save {
+        match fs::write(path, write_baseline(&results)) {
+            Ok(()) => println!("Saved baseline to {}", path.display()),
+            Err(err) => eprintln!("error: could not write {}: {err}", path.display()),
+        }
+    }
+}
+
+/**
+ * Solves every part that has variants with all of them, reports answers that differ from
+ * the default implementation and benchmarks them against each other. Returns `false` if
+ * any answers differ.
+ */
+pub fn compare(options: &Options, settings: &Settings) -> bool {
+    let mut disagreements = 0;
+
+    for number in &options.days {
+        let day = solution::find(*number).unwrap();
+        let variants = day.variants();
+
+        let parts: Vec<_> = options
+            .parts
+            .iter()
+            .filter(|part| variants.iter().any(|(_, variant)| variant == *part))
+            .collect();
+
+        if parts.is_empty() {
+            // Only complain if the day was asked for explicitly.
+            if options.days.len() == 1 {
+                println!("Day {number} has no variants");
+            }
+            continue;
+        }
+
+        println!("## Day {number}");
+
+        let input = match options.input.load(*number) {
+            Ok(input) => input,
+            Err(message) => {
+                eprintln!("error: {message}");
+                continue;
+            }
+        };
+
+        let parsed = match day.parse(&input) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                eprintln!("error: {err}");
+                continue;
+            }
+        };
+        let parsed = parsed.as_ref();
+
+        for part in parts {
+            println!(
+                "{:<16}{:>16}{:>12}{:>12}{:>12}{:>12}",
+                format!("Part {}", part.number()),
+                "answer",
+                "min",
+                "median",
+                "mean",
+                "stddev"
+            );
+
+            let expected = day.solve(parsed, *part);
+            let names = variants
+                .iter()
+                .filter(|(_, variant)| variant == part)
+                .map(|(name, _)| *name);
+
+            for name in std::iter::once("default").chain(names) {
+                let solve = || match name {
+                    "default" => day.solve(parsed, *part),
+                    name => day.solve_variant(parsed, name, *part).unwrap(),
+                };
+
+                let answer = solve();
+                let stats = Stats::from_samples(&sample(settings, solve));
+
+                let note = if answer == expected {
+                    ""
+                } else {
+                    disagreements += 1;
+                    "  differs from default"
+                };
+
+                println!(
+                    "{name:<16}{answer:>16}{:>12.2?}{:>12.2?}{:>12.2?}{:>12.2?}{note}",
+                    stats.min, stats.median, stats.mean, stats.stddev
+                );
+            }
+        }
+    }
+
+    if disagreements > 0 {
+        eprintln!("error: {disagreements} variant(s) disagree with the default");
+    }
+
+    disagreements == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{read_baseline, write_baseline, Key, Stats};
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 8, 6]));
+
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        // sqrt(20 / 3) ms
+        assert_eq!(stats.stddev, Duration::from_nanos(2_581_989));
+    }
+
+    #[test]
+    fn test_stats_of_single_run() {
+        let stats = Stats::from_samples(&millis(&[3]));
+
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let results = vec![
+            (
+                Key {
+                    day: 14,
+                    stage: String::from("parse"),
+                },
+                Stats::from_samples(&millis(&[1, 2, 3])),
+            ),
+            (
+                Key {
+                    day: 14,
+                    stage: String::from("2"),
+                },
+                Stats::from_samples(&millis(&[7])),
+            ),
+        ];
+
+        let baseline = read_baseline(&write_baseline(&results)).unwrap();
+
+        assert_eq!(baseline.len(), 2);
+        for (key, stats) in &results {
+            assert_eq!(baseline.get(key), Some(stats));
+        }
+    }
+
+    #[test]
+    fn test_invalid_baseline() {
+        assert!(read_baseline("14 parse 1 2 3").is_err());
+        assert!(read_baseline("x parse 1 2 3 4").is_err());
+    }
+}
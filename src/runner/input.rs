@@ -0,0 +1,74 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// Relative to the current directory, like `cargo run` and `cargo test` start in the crate.
+pub const DEFAULT_DIR: &str = "src";
+pub const DEFAULT_FILE: &str = "input.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A single file, used for every selected day.
+    Path(PathBuf),
+    Stdin,
+    /// `<dir>/day<N>/<file>`, resolved per day.
+    PerDay {
+        dir: PathBuf,
+        file: String,
+    },
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::PerDay {
+            dir: PathBuf::from(DEFAULT_DIR),
+            file: String::from(DEFAULT_FILE),
+        }
+    }
+}
+
+impl Source {
+    pub fn from_arg(value: &str) -> Self {
+        match value {
+            "-" => Source::Stdin,
+            path => Source::Path(PathBuf::from(path)),
+        }
+    }
+
+    fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            Source::Path(path) => Some(path.clone()),
+            Source::Stdin => None,
+            Source::PerDay { dir, file } => Some(dir.join(format!("day{day}")).join(file)),
+        }
+    }
+
+    pub fn describe(&self, day: u8) -> String {
+        match self.path(day) {
+            Some(path) => path.display().to_string(),
+            None => String::from("<stdin>"),
+        }
+    }
+
+    /**
+     * Trailing line breaks are stripped, so files saved by an editor behave like the
+     * ones the solvers were written against.
+     */
+    pub fn load(&self, day: u8) -> Result<String, String> {
+        let content = match self.path(day) {
+            None => {
+                let mut content = String::new();
+                io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|err| format!("could not read stdin: {err}"))?;
+                content
+            }
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|err| format!("could not read {}: {err}", path.display()))?,
+        };
+
+        Ok(content.trim_end_matches(['\n', '\r']).to_string())
+    }
+}
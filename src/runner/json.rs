@@ -0,0 +1,92 @@
+use std::fmt::Write;
+
+/// The few JSON values the runner writes, enough for flat records.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Number(i128),
+    String(String),
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<i128> for Value {
+    fn from(value: i128) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// An object on a single line, with the fields in the given order.
+pub fn object(fields: &[(&str, Value)]) -> String {
+    let mut out = String::from("{");
+
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(&mut out, key);
+        out.push(':');
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Number(number) => write!(out, "{number}").unwrap(),
+            Value::String(s) => write_string(&mut out, s),
+        }
+    }
+
+    out.push('}');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{object, Value};
+
+    #[test]
+    fn test_object() {
+        assert_eq!(object(&[]), "{}");
+        assert_eq!(
+            object(&[
+                ("day", Value::from(10)),
+                ("answer", Value::from("#.\n\"x\"\\")),
+                ("error", Value::from(None::<String>)),
+            ]),
+            r##"{"day":10,"answer":"#.\n\"x\"\\","error":null}"##
+        );
+        assert_eq!(
+            object(&[("bell", Value::from("\u{7}"))]),
+            r#"{"bell":"\u0007"}"#
+        );
+    }
+}
@@ -0,0 +1,630 @@
+use std::{any::Any, time::Duration};
+
+use crate::{
+    solution::{self, Answer, Part, DAYS},
+    util,
+};
+
+mod bench;
+mod input;
+mod json;
+mod pool;
+mod verify;
+
+pub use bench::Settings;
+pub use input::Source;
+
+pub const USAGE: &str = "\
+usage: advent_of_code_2022 run [DAYS | --all] [PART] [--part PART] [--variant NAME] [--format FORMAT]
+                               [--jobs N] [INPUT OPTIONS]
+       advent_of_code_2022 bench [DAYS] [PART] [--variant NAME] [BENCH OPTIONS] [INPUT OPTIONS]
+       advent_of_code_2022 compare [DAYS] [PART] [--runs N] [--warmup N] [INPUT OPTIONS]
+       advent_of_code_2022 verify [DAYS] [PART] [INPUT OPTIONS]
+
+  DAYS   a single day (`14`), an inclusive range (`3..9`) or `all` (default)
+  PART   `1` or `2`, runs both parts if omitted
+  NAME   an alternative implementation, e.g. `naive` for day 17
+  FORMAT `text` (default) or `json`, one object per line and part with the answer,
+         the parse and solve time in nanoseconds and the input file
+
+  --jobs N        solve up to N parts at once, the output stays in day order (default: 1)
+  -v, --verbose   print debug output of the solvers to stderr, for every command
+
+bench options:
+  --runs N          measured runs per parse and part (default: 50)
+  --warmup N        unmeasured runs before that (default: 5)
+  --save PATH       write the results as a baseline to PATH
+  --baseline PATH   compare the medians with a baseline saved before
+
+input options:
+  --input PATH   read the puzzle input from PATH, or from stdin if PATH is `-`
+  --dir DIR      read `DIR/day<N>/<file>` for every day (default: `src`)
+  --file NAME    the file name inside the day directory (default: `input.txt`)
+
+verify checks the answers against `<dir>/day<N>/answers.txt`, for the selected input and
+every other input of the day with recorded answers. It exits with 1 if any of them fail,
+like compare does if a variant disagrees with the default and run does if an input cannot
+be loaded or parsed or a part fails.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Solve every selected part once.
+    Run,
+    Bench(Settings),
+    /// Check that all variants agree and benchmark them against each other.
+    Compare(Settings),
+    /// Check the answers against the ones recorded for each day.
+    Verify,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    /// JSON Lines, for scripts and dashboards.
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub days: Vec<u8>,
+    pub parts: Vec<Part>,
+    pub variant: Option<String>,
+    pub input: Source,
+    pub format: Format,
+    /// Threads `run` solves on.
+    pub jobs: usize,
+    pub verbose: bool,
+}
+
+impl Options {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut args = args.iter();
+
+        let mut command = match args.next().map(String::as_str) {
+            Some("run") => Command::Run,
+            Some("bench") => Command::Bench(Settings::default()),
+            Some("compare") => Command::Compare(Settings::default()),
+            Some("verify") => Command::Verify,
+            Some(command) => return Err(format!("unknown command `{command}`")),
+            None => return Err(String::from("missing command")),
+        };
+
+        let mut days: Option<Vec<u8>> = None;
+        let mut part: Option<Part> = None;
+        let mut variant: Option<String> = None;
+        let mut input: Option<Source> = None;
+        let mut dir: Option<String> = None;
+        let mut file: Option<String> = None;
+        let mut format = Format::default();
+        let mut jobs = 1;
+        let mut verbose = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    let value = args.next().ok_or("`--part` expects a value")?;
+                    part = Some(parse_part(value)?);
+                }
+                "--variant" => {
+                    variant = Some(args.next().ok_or("`--variant` expects a name")?.clone())
+                }
+                "--input" => {
+                    let value = args.next().ok_or("`--input` expects a path or `-`")?;
+                    input = Some(Source::from_arg(value));
+                }
+                "--runs" | "--warmup" => {
+                    let (Command::Bench(settings) | Command::Compare(settings)) = &mut command
+                    else {
+                        return Err(format!(
+                            "`{arg}` can only be used with `bench` or `compare`"
+                        ));
+                    };
+                    let value = args.next().ok_or(format!("`{arg}` expects a value"))?;
+                    let count = parse_count(arg, value)?;
+
+                    match arg.as_str() {
+                        "--runs" => settings.runs = count,
+                        _ => settings.warmup = count,
+                    }
+                }
+                "--save" | "--baseline" => {
+                    let Command::Bench(settings) = &mut command else {
+                        return Err(format!("`{arg}` can only be used with `bench`"));
+                    };
+                    let value = args.next().ok_or(format!("`{arg}` expects a value"))?;
+
+                    match arg.as_str() {
+                        "--save" => settings.save = Some(value.into()),
+                        _ => settings.baseline = Some(value.into()),
+                    }
+                }
+                "--format" => {
+                    if command != Command::Run {
+                        return Err(String::from("`--format` can only be used with `run`"));
+                    }
+                    format = match args.next().map(String::as_str) {
+                        Some("text") => Format::Text,
+                        Some("json") => Format::Json,
+                        _ => return Err(String::from("`--format` expects `text` or `json`")),
+                    };
+                }
+                "--jobs" => {
+                    if command != Command::Run {
+                        return Err(String::from("`--jobs` can only be used with `run`"));
+                    }
+                    let value = args.next().ok_or("`--jobs` expects a value")?;
+                    jobs = match value.parse::<usize>() {
+                        Ok(jobs) if jobs > 0 => jobs,
+                        _ => {
+                            return Err(format!(
+                                "`--jobs` expects a positive number, got `{value}`"
+                            ))
+                        }
+                    };
+                }
+                "--all" if days.is_none() => days = Some(all_days()),
+                "--all" => return Err(String::from("`--all` cannot be combined with DAYS")),
+                "-v" | "--verbose" => verbose = true,
+                "--dir" => dir = Some(args.next().ok_or("`--dir` expects a directory")?.clone()),
+                "--file" => file = Some(args.next().ok_or("`--file` expects a name")?.clone()),
+                value if days.is_none() => days = Some(parse_days(value)?),
+                value if part.is_none() => part = Some(parse_part(value)?),
+                value => return Err(format!("unexpected argument `{value}`")),
+            }
+        }
+
+        let days = days.unwrap_or_else(all_days);
+
+        let input = match (input, dir, file) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+                return Err(String::from(
+                    "`--input` cannot be combined with `--dir` or `--file`",
+                ))
+            }
+            (Some(_), None, None) if days.len() != 1 => {
+                return Err(String::from("`--input` can only be used with a single day"))
+            }
+            (Some(input), None, None) => input,
+            (None, dir, file) => Source::PerDay {
+                dir: dir.unwrap_or_else(|| input::DEFAULT_DIR.to_string()).into(),
+                file: file.unwrap_or_else(|| input::DEFAULT_FILE.to_string()),
+            },
+        };
+
+        Ok(Options {
+            command,
+            days,
+            parts: match part {
+                Some(part) => vec![part],
+                None => vec![Part::One, Part::Two],
+            },
+            variant,
+            input,
+            format,
+            jobs,
+            verbose,
+        })
+    }
+}
+
+fn all_days() -> Vec<u8> {
+    DAYS.iter().map(|day| day.number).collect()
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("`{value}` is not a day between 1 and 25")),
+    }
+}
+
+fn parse_days(value: &str) -> Result<Vec<u8>, String> {
+    if value == "all" {
+        return Ok(all_days());
+    }
+
+    match value.split_once("..") {
+        None => {
+            let day = parse_day(value)?;
+            match solution::find(day) {
+                Some(_) => Ok(vec![day]),
+                None => Err(format!("day {day} has no solution")),
+            }
+        }
+        Some((from, to)) => {
+            let from = parse_day(from)?;
+            let to = parse_day(to.trim_start_matches('='))?;
+
+            let days: Vec<u8> = all_days()
+                .into_iter()
+                .filter(|day| (from..=to).contains(day))
+                .collect();
+
+            if days.is_empty() {
+                return Err(format!("`{value}` contains no solved day"));
+            }
+
+            Ok(days)
+        }
+    }
+}
+
+fn parse_count(arg: &str, value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if arg != "--runs" || count > 0 => Ok(count),
+        _ => Err(format!("`{arg}` expects a positive number, got `{value}`")),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("`{value}` is not a part, expected 1 or 2")),
+    }
+}
+
+/// Returns `false` if `run` could not solve a part or the checks of the others failed.
+pub fn run(options: &Options) -> bool {
+    util::set_verbose(options.verbose);
+
+    match &options.command {
+        Command::Run => solve(options),
+        Command::Bench(settings) => {
+            bench::bench(options, settings);
+            true
+        }
+        Command::Compare(settings) => bench::compare(options, settings),
+        Command::Verify => verify::verify(options),
+    }
+}
+
+/// The answer of a part and how long it took, or why there is none.
+type Solved = Result<(Answer, Duration), String>;
+
+/// Everything `run` found out about a single day.
+struct Report {
+    day: u8,
+    input: String,
+    /// How long parsing took, or why the input could not be loaded or parsed.
+    parse: Result<Duration, String>,
+    parts: Vec<(Part, Solved)>,
+}
+
+/// Parses the loaded input of a day, how long that took is reported once for all parts.
+fn parse_input(
+    number: u8,
+    input: &Result<String, String>,
+) -> Result<(Box<dyn Any + Send + Sync>, Duration), String> {
+    let input = input.as_ref().map_err(Clone::clone)?;
+    let day = solution::find(number).unwrap();
+
+    match util::measure(|| day.parse(input)) {
+        (Ok(parsed), elapsed) => Ok((parsed, elapsed)),
+        (Err(err), _) => Err(err.to_string()),
+    }
+}
+
+fn solve_part(options: &Options, number: u8, parsed: &dyn Any, part: Part) -> Solved {
+    let day = solution::find(number).unwrap();
+
+    let solved = match &options.variant {
+        None => util::measure(|| day.solve(parsed, part)),
+        Some(name) => match util::measure(|| day.solve_variant(parsed, name, part)) {
+            (Some(answer), elapsed) => (answer, elapsed),
+            (None, _) => return Err(format!("no variant `{name}`")),
+        },
+    };
+
+    // A part that failed is reported like one that could not be run at all.
+    match solved {
+        (Answer::Failed(err), _) => Err(err.to_string()),
+        solved => Ok(solved),
+    }
+}
+
+/**
+ * Every day is parsed once, then every part of it is a job of its own on the parsed input.
+ * Reports are printed in day order, each once all of its parts are solved. Returns `false`
+ * if any input could not be loaded or parsed, or any part failed.
+ */
+fn solve(options: &Options) -> bool {
+    let print = |report: &Report| match options.format {
+        Format::Text => print_text(report),
+        Format::Json => print_json(report, options.variant.as_deref()),
+    };
+
+    // Loaded up front, stdin can only be read once.
+    let inputs: Vec<_> = options
+        .days
+        .iter()
+        .map(|number| options.input.load(*number))
+        .collect();
+
+    let days: Vec<usize> = (0..options.days.len()).collect();
+    let mut parsed = Vec::with_capacity(days.len());
+    let mut reports = Vec::with_capacity(days.len());
+
+    pool::ordered(
+        &days,
+        options.jobs,
+        |i| parse_input(options.days[*i], &inputs[*i]),
+        |i, result| {
+            let result = result.and_then(|result| result);
+            reports.push(Report {
+                day: options.days[*i],
+                input: options.input.describe(options.days[*i]),
+                parse: result
+                    .as_ref()
+                    .map(|(_, elapsed)| *elapsed)
+                    .map_err(Clone::clone),
+                parts: Vec::new(),
+            });
+            parsed.push(result.ok().map(|(parsed, _)| parsed));
+        },
+    );
+
+    let jobs: Vec<(usize, Part)> = days
+        .iter()
+        .filter(|i| parsed[**i].is_some())
+        .flat_map(|i| options.parts.iter().map(move |part| (*i, *part)))
+        .collect();
+
+    // Days that could not be parsed have no jobs and are printed with the next one that has.
+    let mut printed = 0;
+
+    pool::ordered(
+        &jobs,
+        options.jobs,
+        |(i, part)| {
+            let parsed = parsed[*i].as_deref().unwrap();
+            solve_part(options, options.days[*i], parsed, *part)
+        },
+        |(i, part), solved| {
+            let solved = solved.unwrap_or_else(|message| Err(format!("panicked: {message}")));
+            reports[*i].parts.push((*part, solved));
+
+            if reports[*i].parts.len() == options.parts.len() {
+                reports[printed..=*i].iter().for_each(print);
+                printed = i + 1;
+            }
+        },
+    );
+
+    reports[printed..].iter().for_each(print);
+
+    reports
+        .iter()
+        .all(|report| report.parse.is_ok() && report.parts.iter().all(|(_, solved)| solved.is_ok()))
+}
+
+fn print_text(report: &Report) {
+    println!("## Day {}", report.day);
+
+    match &report.parse {
+        Ok(elapsed) => println!("Parse: {:.2?}", elapsed),
+        Err(message) => {
+            eprintln!("error: {message}");
+            return;
+        }
+    }
+
+    for (part, solved) in &report.parts {
+        let (answer, elapsed) = match solved {
+            Ok(solved) => solved,
+            Err(message) => {
+                println!("Part {}: error: {message}", part.number());
+                continue;
+            }
+        };
+
+        // Multi-line answers like rendered letters start on their own line.
+        let separator = if answer.to_string().contains('\n') {
+            "\n"
+        } else {
+            " "
+        };
+        println!(
+            "Part {}:{separator}{answer} (time: {:.2?})",
+            part.number(),
+            elapsed
+        );
+    }
+}
+
+/**
+ * One line per part, or a single one without a part if the input could not be loaded or
+ * parsed. Unsolved parts have neither an answer nor an error.
+ */
+fn print_json(report: &Report, variant: Option<&str>) {
+    let record = |part: Option<Part>, answer: Option<&Answer>, solve: Option<Duration>, error| {
+        let nanos = |duration: Duration| duration.as_nanos() as i128;
+        let answer = match answer {
+            Some(Answer::Number(number)) => json::Value::from(*number),
+            Some(Answer::Text(text)) => json::Value::from(text.as_str()),
+            Some(Answer::Unsolved | Answer::Failed(_)) | None => json::Value::Null,
+        };
+
+        println!(
+            "{}",
+            json::object(&[
+                ("day", i128::from(report.day).into()),
+                ("part", part.map(|part| i128::from(part.number())).into()),
+                ("variant", variant.into()),
+                ("input", report.input.as_str().into()),
+                ("answer", answer),
+                (
+                    "parse_ns",
+                    report.parse.as_ref().ok().map(|d| nanos(*d)).into()
+                ),
+                ("solve_ns", solve.map(nanos).into()),
+                ("error", error),
+            ])
+        );
+    };
+
+    if let Err(message) = &report.parse {
+        record(None, None, None, message.as_str().into());
+    }
+
+    for (part, solved) in &report.parts {
+        match solved {
+            Ok((answer, elapsed)) => {
+                record(Some(*part), Some(answer), Some(*elapsed), json::Value::Null)
+            }
+            Err(message) => record(Some(*part), None, None, message.as_str().into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{input, Command, Format, Options, Part, Settings, Source};
+
+    fn parse(args: &str) -> Result<Options, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        Options::from_args(&args)
+    }
+
+    #[test]
+    fn test_single_day_and_part() {
+        assert_eq!(
+            parse("run 14 2"),
+            Ok(Options {
+                command: Command::Run,
+                days: vec![14],
+                parts: vec![Part::Two],
+                variant: None,
+                input: Source::default(),
+                format: Format::Text,
+                jobs: 1,
+                verbose: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_day_range() {
+        assert_eq!(parse("run 3..9").unwrap().days, (3..=9).collect::<Vec<_>>());
+        assert_eq!(
+            parse("run 3..=9").unwrap().days,
+            (3..=9).collect::<Vec<_>>()
+        );
+        assert!(parse("run 9..3").is_err());
+    }
+
+    #[test]
+    fn test_all_days_with_part_flag() {
+        let options = parse("run --part 1").unwrap();
+        assert_eq!(options.days, parse("run all").unwrap().days);
+        assert_eq!(options.parts, vec![Part::One]);
+    }
+
+    #[test]
+    fn test_variant() {
+        let options = parse("run 17 1 --variant naive").unwrap();
+        assert_eq!(options.variant.as_deref(), Some("naive"));
+        assert!(parse("run 17 --variant").is_err());
+    }
+
+    #[test]
+    fn test_bench_settings() {
+        assert_eq!(parse("run 3").unwrap().command, Command::Run,);
+        assert_eq!(
+            parse("bench 3").unwrap().command,
+            Command::Bench(Settings::default())
+        );
+        assert_eq!(
+            parse("bench 3 2 --runs 10 --warmup 0 --save new.txt --baseline old.txt")
+                .unwrap()
+                .command,
+            Command::Bench(Settings {
+                runs: 10,
+                warmup: 0,
+                save: Some("new.txt".into()),
+                baseline: Some("old.txt".into()),
+            })
+        );
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --runs").is_err());
+        assert!(parse("run --runs 10").is_err());
+        assert_eq!(
+            parse("compare 8 --runs 3").unwrap().command,
+            Command::Compare(Settings {
+                runs: 3,
+                ..Settings::default()
+            })
+        );
+        assert!(parse("compare 8 --save new.txt").is_err());
+        assert!(parse("verify --runs 3").is_err());
+    }
+
+    #[test]
+    fn test_format_and_verbosity() {
+        let options = parse("run 11 --format json -v").unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert!(options.verbose);
+        assert!(parse("bench 11 --verbose").unwrap().verbose);
+
+        assert!(parse("run 11 --format yaml").is_err());
+        assert!(parse("bench 11 --format json").is_err());
+    }
+
+    #[test]
+    fn test_jobs() {
+        let options = parse("run --all 2 --jobs 8").unwrap();
+        assert_eq!(options.days, parse("run all").unwrap().days);
+        assert_eq!(options.parts, vec![Part::Two]);
+        assert_eq!(options.jobs, 8);
+        assert_eq!(parse("run 3").unwrap().jobs, 1);
+
+        assert!(parse("run --all --jobs 0").is_err());
+        assert!(parse("run 3 --all").is_err());
+        assert!(parse("bench --all --jobs 2").is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let options = parse("verify 13 --file input-test.txt").unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(
+            options.input,
+            Source::PerDay {
+                dir: input::DEFAULT_DIR.into(),
+                file: String::from("input-test.txt")
+            }
+        );
+    }
+
+    #[test]
+    fn test_input_sources() {
+        assert_eq!(parse("run 1 --input -").unwrap().input, Source::Stdin);
+        assert_eq!(
+            parse("run 1 --input day1.txt").unwrap().input,
+            Source::Path("day1.txt".into())
+        );
+        assert_eq!(
+            parse("run all --dir inputs --file input-test.txt")
+                .unwrap()
+                .input,
+            Source::PerDay {
+                dir: "inputs".into(),
+                file: String::from("input-test.txt")
+            }
+        );
+        assert!(parse("run all --input -").is_err());
+        assert!(parse("run 1 --input - --file input-test.txt").is_err());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse("").is_err());
+        assert!(parse("walk 1").is_err());
+        assert!(parse("run 0").is_err());
+        assert!(parse("run 25").is_err());
+        assert!(parse("run 1 3").is_err());
+        assert!(parse("run 1 2 extra").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// The message a panic was started with, if it has one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => String::from("unknown panic"),
+    }
+}
+
+/// Runs `work` for a single job, with a panic turned into its message.
+fn run<J, R>(work: impl Fn(&J) -> R, job: &J) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| work(job))).map_err(|panic| panic_message(&*panic))
+}
+
+/**
+ * Runs `work` for every job on up to `threads` threads. The results are handed to `done`
+ * with their job in the order of the jobs, each as soon as it and all before it are
+ * finished. A job that panics hands over the message of the panic instead, and the others
+ * carry on.
+ *
+ * A single thread runs everything on the calling one.
+ */
+pub fn ordered<J, R>(
+    jobs: &[J],
+    threads: usize,
+    work: impl Fn(&J) -> R + Sync,
+    mut done: impl FnMut(&J, Result<R, String>),
+) where
+    J: Sync,
+    R: Send,
+{
+    if threads <= 1 {
+        for job in jobs {
+            done(job, run(&work, job));
+        }
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.min(jobs.len()) {
+            let (next, work, sender) = (&next, &work, sender.clone());
+
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(index) else {
+                    break;
+                };
+                // The receiver only hangs up if `done` panicked.
+                if sender.send((index, run(work, job))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut expected = 0;
+
+        for (index, result) in receiver {
+            finished.insert(index, result);
+
+            while let Some(result) = finished.remove(&expected) {
+                done(&jobs[expected], result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::ordered;
+
+    #[test]
+    fn test_results_in_job_order() {
+        let jobs: Vec<u64> = (0..20).collect();
+
+        for threads in [1, 4] {
+            let mut results = vec![];
+            // Earlier jobs take longer, so they finish last.
+            ordered(
+                &jobs,
+                threads,
+                |job| {
+                    thread::sleep(Duration::from_millis(20 - job));
+                    job * 2
+                },
+                |_, result| results.push(result.unwrap()),
+            );
+
+            assert_eq!(results, jobs.iter().map(|job| job * 2).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_panic_does_not_block_later_jobs() {
+        let jobs: Vec<u64> = (0..6).collect();
+
+        for threads in [1, 3] {
+            let mut results = vec![];
+            ordered(
+                &jobs,
+                threads,
+                |job| match job {
+                    2 => panic!("job {job} failed"),
+                    job => job * 2,
+                },
+                |job, result| results.push((*job, result)),
+            );
+
+            assert_eq!(results.len(), 6);
+            assert_eq!(results[2], (2, Err(String::from("job 2 failed"))));
+            assert_eq!(results[5], (5, Ok(10)));
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use super::{input, Options, Source};
+use crate::{
+    solution::{self, Answer, Part},
+    util::error::{self, Error},
+};
+
+pub const MANIFEST_FILE: &str = "answers.txt";
+
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub part: Part,
+    pub file: String,
+    pub answer: String,
+    /// `false` for a guess that was rejected.
+    pub correct: bool,
+    pub note: Option<String>,
+}
+
+/**
+ * The known answers of a day, one per line and `#` for comments:
+ *
+ * ```text
+ * 1 input-test.txt = 1651
+ * 1 input.txt != 1838 too low
+ * ```
+ *
+ * `=` records the correct answer of a part for an input file, `!=` a wrong guess with an
+ * optional note. Line breaks in answers are written as `\n`.
+ */
+#[derive(Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub entries: Vec<Entry>,
+}
+
+impl FromStr for Manifest {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let entries = s
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| {
+                let mut parts = line.split_whitespace();
+
+                let part = match error::next(&mut parts, line, "a part")? {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    part => return Err(Error::at(part, format!("`{part}` is not a part"))),
+                };
+                let file = error::next(&mut parts, line, "an input file")?;
+                let correct = match error::next(&mut parts, line, "`=` or `!=`")? {
+                    "=" => true,
+                    "!=" => false,
+                    relation => {
+                        return Err(Error::at(relation, "expected `=` or `!=`"));
+                    }
+                };
+                let answer = error::next(&mut parts, line, "an answer")?;
+                let note = parts.collect::<Vec<_>>().join(" ");
+
+                Ok(Entry {
+                    part,
+                    file: file.to_string(),
+                    answer: answer.replace("\\n", "\n"),
+                    correct,
+                    note: (!note.is_empty()).then_some(note),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Manifest { entries })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict<'a> {
+    Pass,
+    /// The correct answer, if it is known.
+    Fail(Option<&'a str>),
+    /// One of the wrong guesses.
+    KnownWrong(Option<&'a str>),
+    Unknown,
+    Unsolved,
+}
+
+impl Manifest {
+    /// Input files with recorded answers, in the order of the manifest.
+    pub fn files(&self) -> Vec<&str> {
+        let mut files: Vec<&str> = vec![];
+        for entry in &self.entries {
+            if !files.contains(&entry.file.as_str()) {
+                files.push(&entry.file);
+            }
+        }
+        files
+    }
+
+    pub fn check(&self, part: Part, file: &str, answer: &Answer) -> Verdict<'_> {
+        match answer {
+            Answer::Unsolved => return Verdict::Unsolved,
+            Answer::Failed(_) => return Verdict::Fail(None),
+            _ => {}
+        }
+
+        let answer = answer.to_string();
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.part == part && entry.file == file);
+
+        let mut verdict = Verdict::Unknown;
+        for entry in entries {
+            match (entry.correct, entry.answer == answer) {
+                (true, true) => return Verdict::Pass,
+                (true, false) => verdict = Verdict::Fail(Some(&entry.answer)),
+                (false, true) => return Verdict::KnownWrong(entry.note.as_deref()),
+                (false, false) => {}
+            }
+        }
+
+        verdict
+    }
+}
+
+fn load_manifest(path: &Path, day: u8) -> Result<Manifest, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) if !path.exists() => return Ok(Manifest::default()),
+        Err(err) => return Err(format!("could not read {}: {err}", path.display())),
+    };
+
+    content
+        .parse()
+        .map_err(|err: Error| format!("{}: {}", path.display(), err.in_day(day).locate(&content)))
+}
+
+/// Solves every part on every input file with recorded answers, returns if none failed.
+pub fn verify(options: &Options) -> bool {
+    let dir = match &options.input {
+        Source::PerDay { dir, .. } => dir.clone(),
+        _ => PathBuf::from(input::DEFAULT_DIR),
+    };
+
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    for number in &options.days {
+        println!("## Day {number}");
+
+        let day = solution::find(*number).unwrap();
+        let manifest = match load_manifest(
+            &dir.join(format!("day{number}")).join(MANIFEST_FILE),
+            *number,
+        ) {
+            Ok(manifest) => manifest,
+            Err(message) => {
+                eprintln!("error: {message}");
+                failed += 1;
+                continue;
+            }
+        };
+
+        // The selected input first, then everything else with known answers.
+        let mut sources = vec![options.input.clone()];
+        for file in manifest.files() {
+            let source = Source::PerDay {
+                dir: dir.clone(),
+                file: file.to_string(),
+            };
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+
+        for source in sources {
+            let name = source.describe(*number);
+            let file = Path::new(&name)
+                .file_name()
+                .map_or(name.clone(), |file| file.to_string_lossy().to_string());
+
+            let input = match source.load(*number) {
+                Ok(input) => input,
+                // Missing default inputs are fine, missing inputs with answers are not.
+                Err(_) if !manifest.files().contains(&file.as_str()) => continue,
+                Err(message) => {
+                    println!("{file}: error: {message}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let parsed = match day.parse(&input) {
+                Ok(parsed) => parsed,
+                Err(err) => {
+                    println!("{file}: error: {err}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            for part in &options.parts {
+                let solved =
+                    panic::catch_unwind(AssertUnwindSafe(|| day.solve(parsed.as_ref(), *part)));
+
+                let (answer, verdict) = match &solved {
+                    Ok(answer) => (answer.to_string(), manifest.check(*part, &file, answer)),
+                    Err(_) => (String::from("panicked"), Verdict::Fail(None)),
+                };
+                // Rendered letters would break the table.
+                let answer = answer.replace('\n', "|");
+
+                let result = match verdict {
+                    Verdict::Pass => {
+                        passed += 1;
+                        String::from("pass")
+                    }
+                    Verdict::Fail(expected) => {
+                        failed += 1;
+                        match expected {
+                            Some(expected) => {
+                                format!("FAIL, expected {}", expected.replace('\n', "|"))
+                            }
+                            None => String::from("FAIL"),
+                        }
+                    }
+                    Verdict::KnownWrong(note) => {
+                        failed += 1;
+                        match note {
+                            Some(note) => format!("FAIL, known wrong ({note})"),
+                            None => String::from("FAIL, known wrong"),
+                        }
+                    }
+                    Verdict::Unknown => {
+                        unknown += 1;
+                        String::from("unknown")
+                    }
+                    Verdict::Unsolved => {
+                        unknown += 1;
+                        String::from("unsolved")
+                    }
+                };
+
+                println!("{file:<20}Part {}: {answer:<20} {result}", part.number());
+            }
+        }
+    }
+
+    println!("\n{passed} passed, {failed} failed, {unknown} unknown");
+
+    failed == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, Verdict, MANIFEST_FILE};
+    use crate::{
+        runner::Source,
+        solution::{Answer, Part, DAYS},
+        util::Error,
+    };
+
+    static MANIFEST: &str = "\
+# Day 16
+1 input-test.txt = 1651
+1 input.txt != 1838 too low
+1 input.txt != 1880 too high
+
+2 input.txt = ##.\\n.##";
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: Manifest = MANIFEST.parse().unwrap();
+
+        assert_eq!(manifest.entries.len(), 4);
+        assert_eq!(manifest.files(), vec!["input-test.txt", "input.txt"]);
+        assert_eq!(manifest.entries[1].note.as_deref(), Some("too low"));
+        assert_eq!(manifest.entries[3].answer, "##.\n.##");
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        let err = "1 input.txt ~ 5".parse::<Manifest>().unwrap_err();
+        assert_eq!(err.message, "expected `=` or `!=`");
+
+        assert!("3 input.txt = 5".parse::<Manifest>().is_err());
+        assert!("1 input.txt =".parse::<Manifest>().is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let manifest: Manifest = MANIFEST.parse().unwrap();
+
+        let check = |part, file, answer: Answer| manifest.check(part, file, &answer);
+
+        assert_eq!(
+            check(Part::One, "input-test.txt", Answer::from(1651)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            check(Part::One, "input-test.txt", Answer::from(1650)),
+            Verdict::Fail(Some("1651"))
+        );
+        assert_eq!(
+            check(Part::One, "input.txt", Answer::from(1838)),
+            Verdict::KnownWrong(Some("too low"))
+        );
+        assert_eq!(
+            check(Part::One, "input.txt", Answer::from(1857)),
+            Verdict::Unknown
+        );
+        assert_eq!(
+            check(Part::Two, "input.txt", Answer::from("##.\n.##")),
+            Verdict::Pass
+        );
+        assert_eq!(
+            check(Part::Two, "input-test.txt", Answer::Unsolved),
+            Verdict::Unsolved
+        );
+        assert_eq!(
+            check(
+                Part::Two,
+                "input-test.txt",
+                Answer::Failed(Error::new("no path"))
+            ),
+            Verdict::Fail(None)
+        );
+    }
+
+    #[test]
+    fn test_every_manifest_parses() {
+        let Source::PerDay { dir, .. } = Source::default() else {
+            unreachable!();
+        };
+
+        for day in DAYS {
+            let path = dir.join(format!("day{}", day.number)).join(MANIFEST_FILE);
+            if let Err(message) = super::load_manifest(&path, day.number) {
+                panic!("{message}");
+            }
+        }
+    }
+}
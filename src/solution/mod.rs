@@ -0,0 +1,311 @@
+use std::{any::Any, fmt};
+
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day2, day20, day21, day22,
+    day23, day3, day4, day5, day6, day7, day8, day9, util::Error,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    /// The part has no implementation (yet).
+    Unsolved,
+    /// The input is well-formed, but has no answer, like a map without a way to the exit.
+    Failed(Error),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(value) => f.pad(&value.to_string()),
+            Answer::Text(value) => f.pad(value),
+            Answer::Unsolved => f.pad("unsolved"),
+            Answer::Failed(err) => f.pad(&format!("error: {err}")),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(value: $number) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u8, u32, u64, u128, usize, i32, i64, i128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Result<T, Error>> for Answer {
+    fn from(result: Result<T, Error>) -> Self {
+        match result {
+            Ok(value) => value.into(),
+            Err(err) => Answer::Failed(err),
+        }
+    }
+}
+
+/// An alternative implementation of one part, selectable by its name.
+pub struct Variant<P> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&P) -> Answer,
+}
+
+/**
+ * A day is parsed once and both parts are solved on the parsed input, so parts must not
+ * modify it and clone whatever they need to mutate. Malformed input is reported by
+ * `parse`, the parts may assume it is well-formed. Input that is well-formed but has no
+ * answer is reported by the part as [`Answer::Failed`].
+ */
+pub trait Solution {
+    /// Shared between the threads that solve the parts.
+    type Parsed: Send + Sync + 'static;
+
+    fn parse(input: &str) -> Result<Self::Parsed, Error>;
+
+    fn part_one(parsed: &Self::Parsed) -> Answer;
+
+    fn part_two(_parsed: &Self::Parsed) -> Answer {
+        Answer::Unsolved
+    }
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[];
+
+    fn solve(input: &str, part: Part) -> Result<Answer, Error> {
+        let parsed = Self::parse(input).map_err(|err| err.locate(input))?;
+
+        Ok(match part {
+            Part::One => Self::part_one(&parsed),
+            Part::Two => Self::part_two(&parsed),
+        })
+    }
+}
+
+/// A type erased [`Solution`], so that all days fit into one registry.
+pub struct Day {
+    pub number: u8,
+    parse: fn(&str) -> Result<Box<dyn Any + Send + Sync>, Error>,
+    part_one: fn(&dyn Any) -> Answer,
+    part_two: fn(&dyn Any) -> Answer,
+    variants: fn() -> Vec<(&'static str, Part)>,
+    solve_variant: fn(&dyn Any, &str, Part) -> Option<Answer>,
+}
+
+fn parse_erased<S: Solution>(input: &str) -> Result<Box<dyn Any + Send + Sync>, Error> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn part_one_erased<S: Solution>(parsed: &dyn Any) -> Answer {
+    S::part_one(parsed.downcast_ref().unwrap())
+}
+
+fn part_two_erased<S: Solution>(parsed: &dyn Any) -> Answer {
+    S::part_two(parsed.downcast_ref().unwrap())
+}
+
+fn variants_erased<S: Solution>() -> Vec<(&'static str, Part)> {
+    S::VARIANTS
+        .iter()
+        .map(|variant| (variant.name, variant.part))
+        .collect()
+}
+
+fn solve_variant_erased<S: Solution>(parsed: &dyn Any, name: &str, part: Part) -> Option<Answer> {
+    S::VARIANTS
+        .iter()
+        .find(|variant| variant.name == name && variant.part == part)
+        .map(|variant| (variant.solve)(parsed.downcast_ref().unwrap()))
+}
+
+impl Day {
+    pub const fn new<S: Solution>(number: u8) -> Self {
+        Day {
+            number,
+            parse: parse_erased::<S>,
+            part_one: part_one_erased::<S>,
+            part_two: part_two_erased::<S>,
+            variants: variants_erased::<S>,
+            solve_variant: solve_variant_erased::<S>,
+        }
+    }
+
+    /// Errors carry the day and their position in `input`.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Any + Send + Sync>, Error> {
+        (self.parse)(input).map_err(|err| err.in_day(self.number).locate(input))
+    }
+
+    /// `parsed` has to be the result of [`Day::parse`] of the same day.
+    pub fn solve(&self, parsed: &dyn Any, part: Part) -> Answer {
+        match part {
+            Part::One => (self.part_one)(parsed),
+            Part::Two => (self.part_two)(parsed),
+        }
+    }
+
+    /// Names and parts of the registered [`Variant`]s.
+    pub fn variants(&self) -> Vec<(&'static str, Part)> {
+        (self.variants)()
+    }
+
+    /// Like [`Day::solve`], but with the variant `name`, if the part has one.
+    pub fn solve_variant(&self, parsed: &dyn Any, name: &str, part: Part) -> Option<Answer> {
+        (self.solve_variant)(parsed, name, part)
+    }
+}
+
+pub static DAYS: &[Day] = &[
+    Day::new::<day1::Day1>(1),
+    Day::new::<day2::Day2>(2),
+    Day::new::<day3::Day3>(3),
+    Day::new::<day4::Day4>(4),
+    Day::new::<day5::Day5>(5),
+    Day::new::<day6::Day6>(6),
+    Day::new::<day7::Day7>(7),
+    Day::new::<day8::Day8>(8),
+    Day::new::<day9::Day9>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+    Day::new::<day22::Day22>(22),
+    Day::new::<day23::Day23>(23),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, Part, DAYS};
+    use crate::{runner::Source, util::Error};
+
+    #[test]
+    fn test_registry_is_ordered() {
+        for pair in DAYS.windows(2) {
+            assert!(pair[0].number < pair[1].number);
+        }
+    }
+
+    #[test]
+    fn test_registry_solves_sample() {
+        let day = super::find(1).unwrap();
+        let parsed = day.parse("1000\n2000\n\n4000\n\n5000\n6000").unwrap();
+
+        assert_eq!(day.solve(parsed.as_ref(), Part::One), Answer::from(11000));
+        assert_eq!(day.solve(parsed.as_ref(), Part::Two), Answer::from(18000));
+    }
+
+    #[test]
+    fn test_registry_solves_every_input() {
+        let source = Source::default();
+
+        for day in DAYS {
+            // Not every day ships its puzzle input.
+            let Ok(input) = source.load(day.number) else {
+                continue;
+            };
+
+            let parsed = day.parse(&input).unwrap();
+            day.solve(parsed.as_ref(), Part::One);
+            day.solve(parsed.as_ref(), Part::Two);
+        }
+    }
+
+    #[test]
+    fn test_variants_agree_on_every_input() {
+        for day in DAYS.iter().filter(|day| !day.variants().is_empty()) {
+            let Source::PerDay { dir, .. } = Source::default() else {
+                unreachable!();
+            };
+            let Ok(entries) = std::fs::read_dir(dir.join(format!("day{}", day.number))) else {
+                continue;
+            };
+
+            for entry in entries {
+                let file = entry.unwrap().file_name().to_string_lossy().to_string();
+                if !file.starts_with("input") || !file.ends_with(".txt") {
+                    continue;
+                }
+
+                let source = Source::PerDay {
+                    dir: dir.clone(),
+                    file: file.clone(),
+                };
+                // Not every file next to a day is a puzzle input, like the rocks of day 17.
+                let Ok(parsed) = day.parse(&source.load(day.number).unwrap()) else {
+                    continue;
+                };
+
+                for (name, part) in day.variants() {
+                    assert_eq!(
+                        day.solve_variant(parsed.as_ref(), name, part),
+                        Some(day.solve(parsed.as_ref(), part)),
+                        "day {} part {} variant `{name}` on {file}",
+                        day.number,
+                        part.number()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_reports_malformed_input() {
+        let day = super::find(1).unwrap();
+        let err = day.parse("1000\n2000\n\nfour").err().unwrap();
+
+        assert_eq!(err.day, Some(1));
+        assert_eq!(err.position.unwrap().line, 4);
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42u64).to_string(), "42");
+        assert_eq!(Answer::from("SHQWSRBDL").to_string(), "SHQWSRBDL");
+        assert_eq!(Answer::Unsolved.to_string(), "unsolved");
+        assert_eq!(
+            Answer::from(Err::<u64, _>(Error::new("there is no path"))).to_string(),
+            "error: there is no path"
+        );
+    }
+}
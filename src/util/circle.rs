@@ -0,0 +1,318 @@
+use std::ops::Index;
+
+/// The default for [`Circle::with_block_size`].
+const BLOCK_SIZE: usize = 64;
+
+/// Sums of block lengths, so positions can be looked up without walking all blocks.
+#[derive(Debug, Clone, Default)]
+struct Fenwick {
+    /// One based, `tree[i]` sums the `i & i.wrapping_neg()` lengths up to block `i - 1`.
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    fn new(lengths: impl Iterator<Item = usize>) -> Self {
+        let mut tree: Vec<usize> = std::iter::once(0).chain(lengths).collect();
+        for i in 1..tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+
+        Fenwick { tree }
+    }
+
+    fn blocks(&self) -> usize {
+        self.tree.len().saturating_sub(1)
+    }
+
+    /// Adds a block of `length` at the end.
+    fn push(&mut self, length: usize) {
+        if self.tree.is_empty() {
+            self.tree.push(0);
+        }
+        let i = self.tree.len();
+        let covered = self.before(i - 1) - self.before(i - (i & i.wrapping_neg()));
+        self.tree.push(length + covered);
+    }
+
+    fn add(&mut self, block: usize, change: isize) {
+        let mut i = block + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i].checked_add_signed(change).unwrap();
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The length of all blocks before `block`.
+    fn before(&self, block: usize) -> usize {
+        let (mut i, mut sum) = (block, 0);
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    /// The block that holds `position` and the offset in it, or the number of blocks if none.
+    fn find(&self, mut position: usize) -> (usize, usize) {
+        let blocks = self.blocks();
+        let mut block = 0;
+        let mut step = if blocks == 0 { 0 } else { 1 << blocks.ilog2() };
+        while step > 0 {
+            if block + step <= blocks && self.tree[block + step] <= position {
+                block += step;
+                position -= self.tree[block];
+            }
+            step >>= 1;
+        }
+        (block, position)
+    }
+}
+
+/**
+ * A circular list that elements can be moved around in, while still being found by the id
+ * they were pushed with. The ids are kept in order in short blocks, every id remembers its
+ * block, and a Fenwick tree over the block lengths turns a block into a position and back.
+ * Finding, removing and inserting take O(log n) plus a pass over one block.
+ */
+#[derive(Debug, Clone)]
+pub struct Circle<T> {
+    /// Blocks are split up again once they are twice as long.
+    block_size: usize,
+    values: Vec<T>,
+    block_of: Vec<usize>,
+    blocks: Vec<Vec<usize>>,
+    lengths: Fenwick,
+}
+
+impl<T> Circle<T> {
+    pub fn new() -> Self {
+        Self::with_block_size(BLOCK_SIZE)
+    }
+
+    /**
+     * Longer blocks make finding an element slower and moving one between them faster,
+     * about the square root of the length is a good start.
+     */
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size > 0, "blocks need room for an element");
+
+        Circle {
+            block_size,
+            values: vec![],
+            block_of: vec![],
+            blocks: vec![],
+            lengths: Fenwick::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Appends `value` at the end and returns its id.
+    pub fn push(&mut self, value: T) -> usize {
+        let id = self.values.len();
+        self.values.push(value);
+
+        if self
+            .blocks
+            .last()
+            .is_none_or(|block| block.len() >= self.block_size)
+        {
+            self.blocks.push(vec![]);
+            self.lengths.push(0);
+        }
+        let block = self.blocks.len() - 1;
+        self.blocks[block].push(id);
+        self.block_of.push(block);
+        self.lengths.add(block, 1);
+
+        id
+    }
+
+    /// Where the element with `id` currently is, counted from the start of the list.
+    pub fn position(&self, id: usize) -> usize {
+        let block = self.block_of[id];
+        self.lengths.before(block) + self.offset(block, id)
+    }
+
+    /// The id of the element at `position`, which wraps around.
+    pub fn id_at(&self, position: usize) -> usize {
+        let (block, offset) = self.lengths.find(position % self.len());
+        self.blocks[block][offset]
+    }
+
+    /// The value at `position`, which wraps around.
+    pub fn get(&self, position: usize) -> &T {
+        &self[self.id_at(position)]
+    }
+
+    /**
+     * Moves the element with `id` by `steps` positions, backwards if negative. It is lifted
+     * out of the circle while moving, so going all the way around takes one step less than
+     * the length of the list.
+     */
+    pub fn move_by(&mut self, id: usize, steps: i64) {
+        if self.len() < 2 {
+            return;
+        }
+
+        let block = self.block_of[id];
+        let offset = self.offset(block, id);
+        let from = self.lengths.before(block) + offset;
+        self.blocks[block].remove(offset);
+        self.lengths.add(block, -1);
+
+        let len = self.len() - 1;
+        let to = (from + steps.rem_euclid(len as i64) as usize) % len;
+        let (mut block, mut offset) = self.lengths.find(to);
+        if block == self.blocks.len() {
+            block -= 1;
+            offset = self.blocks[block].len();
+        }
+        self.blocks[block].insert(offset, id);
+        self.block_of[id] = block;
+        self.lengths.add(block, 1);
+
+        if self.blocks[block].len() >= 2 * self.block_size {
+            self.rebuild();
+        }
+    }
+
+    /// The values in the order of the list.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.blocks.iter().flatten().map(|id| &self.values[*id])
+    }
+
+    fn offset(&self, block: usize, id: usize) -> usize {
+        self.blocks[block]
+            .iter()
+            .position(|other| *other == id)
+            .unwrap()
+    }
+
+    /// Splits the list into even blocks again.
+    fn rebuild(&mut self) {
+        let order: Vec<usize> = self.blocks.iter().flatten().copied().collect();
+        self.blocks = order
+            .chunks(self.block_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+        for (block, ids) in self.blocks.iter().enumerate() {
+            for id in ids {
+                self.block_of[*id] = block;
+            }
+        }
+        self.lengths = Fenwick::new(self.blocks.iter().map(Vec::len));
+    }
+}
+
+impl<T> Default for Circle<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The value of the element with the id.
+impl<T> Index<usize> for Circle<T> {
+    type Output = T;
+
+    fn index(&self, id: usize) -> &T {
+        &self.values[id]
+    }
+}
+
+impl<T> Extend<T> for Circle<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Circle<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut circle = Circle::new();
+        circle.extend(iter);
+        circle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Circle;
+
+    /// Moves in a plain vector, the slow way.
+    fn move_by(values: &mut Vec<usize>, id: usize, steps: i64) {
+        let from = values.iter().position(|value| *value == id).unwrap();
+        values.remove(from);
+        let to = (from as i128 + steps as i128).rem_euclid(values.len() as i128) as usize;
+        values.insert(to, id);
+    }
+
+    #[test]
+    fn test_positions() {
+        let circle: Circle<_> = (0..100).map(|id| id * 2).collect();
+
+        assert_eq!(circle.len(), 100);
+        for id in 0..100 {
+            assert_eq!(circle.position(id), id);
+            assert_eq!(circle.id_at(id), id);
+            assert_eq!(circle[id], id * 2);
+        }
+        assert_eq!(*circle.get(105), 10);
+    }
+
+    #[test]
+    fn test_move_by() {
+        let mut circle: Circle<_> = "abcd".chars().collect();
+
+        circle.move_by(0, 1);
+        assert_eq!(circle.iter().collect::<String>(), "bacd");
+        circle.move_by(0, 2);
+        assert_eq!(circle.iter().collect::<String>(), "abcd");
+        circle.move_by(0, 1);
+        assert_eq!(circle.iter().collect::<String>(), "bacd");
+        circle.move_by(3, -4);
+        assert_eq!(circle.iter().collect::<String>(), "badc");
+        circle.move_by(2, 3);
+        assert_eq!(circle.iter().collect::<String>(), "cbad");
+    }
+
+    #[test]
+    fn test_move_like_a_vector() {
+        let len = 500;
+
+        for block_size in [1, 3, 64] {
+            let mut circle = Circle::with_block_size(block_size);
+            circle.extend(0..len);
+            let mut expected: Vec<_> = (0..len).collect();
+
+            for round in 0..10 {
+                for id in 0..len {
+                    let steps = (id as i64 * 7919 + round * 104729) % 1000 - 500;
+                    circle.move_by(id, steps);
+                    move_by(&mut expected, id, steps);
+                }
+                for id in [0, len / 2] {
+                    circle.move_by(id, i64::MAX - id as i64);
+                    move_by(&mut expected, id, i64::MAX - id as i64);
+                    circle.move_by(id, i64::MIN);
+                    move_by(&mut expected, id, i64::MIN);
+                }
+
+                assert_eq!(circle.iter().copied().collect::<Vec<_>>(), expected);
+                for (position, id) in expected.iter().enumerate() {
+                    assert_eq!(circle.position(*id), position);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{fmt, str::FromStr};
+
+/// Where in the puzzle input an [`Error`] happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    /// Starts at 1, like in an editor.
+    pub line: usize,
+    /// Starts at 1 and counts characters, not bytes.
+    pub column: usize,
+    pub text: String,
+}
+
+/**
+ * Parsers only know the token that did not fit. As every token is a slice of the puzzle
+ * input, its address is kept until [`Error::locate`] turns it into a line and column of
+ * the whole input.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub message: String,
+    pub day: Option<u8>,
+    pub position: Option<Position>,
+    token: Option<usize>,
+}
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            day: None,
+            position: None,
+            token: None,
+        }
+    }
+
+    /// An error pointing at `token`, which has to be a slice of the puzzle input.
+    pub fn at(token: &str, message: impl Into<String>) -> Self {
+        Error {
+            token: Some(token.as_ptr() as usize),
+            ..Error::new(message)
+        }
+    }
+
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    /// Resolves the position of the token, if it is part of `input`.
+    pub fn locate(mut self, input: &str) -> Self {
+        let start = input.as_ptr() as usize;
+
+        let offset = match self.token {
+            Some(token) if (start..=start + input.len()).contains(&token) => token - start,
+            _ => return self,
+        };
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |index| offset + index);
+
+        self.position = Some(Position {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end].to_string(),
+        });
+        self.token = None;
+
+        self
+    }
+}
+
+/**
+ * ```text
+ * day 14, line 3, column 5: expected `,` in `498`
+ *     3 | 498 -> 498,6
+ *       |     ^
+ * ```
+ */
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+
+        match &self.position {
+            None => write!(f, "{}", self.message),
+            Some(position) => {
+                let number = position.line.to_string();
+                let padding = " ".repeat(number.len());
+
+                writeln!(
+                    f,
+                    "line {}, column {}: {}",
+                    position.line, position.column, self.message
+                )?;
+                writeln!(f, "    {number} | {}", position.text)?;
+                write!(f, "    {padding} | {}^", " ".repeat(position.column - 1))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Like [`str::split_once`], but reports a missing delimiter.
+pub fn split_once<'a>(s: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), Error> {
+    s.split_once(delimiter)
+        .ok_or_else(|| Error::at(end_of(s), format!("expected `{delimiter}` in `{s}`")))
+}
+
+/// Parses a number, pointing at `token` if it is none.
+pub fn number<T>(token: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token
+        .parse()
+        .map_err(|err| Error::at(token, format!("invalid number `{token}`: {err}")))
+}
+
+/// The next part of a split, or an error pointing behind `s` if there is none left.
+pub fn next<'a>(
+    parts: &mut impl Iterator<Item = &'a str>,
+    s: &'a str,
+    expected: &str,
+) -> Result<&'a str, Error> {
+    parts
+        .next()
+        .ok_or_else(|| Error::at(end_of(s), format!("expected {expected}")))
+}
+
+/// An empty slice behind `s`, to point at whatever is missing there.
+pub fn end_of(s: &str) -> &str {
+    &s[s.len()..]
+}
+
+/// The `n`th character of `s` as a slice, or an empty slice behind `s` if it is shorter.
+pub fn nth_char(s: &str, n: usize) -> &str {
+    match s.char_indices().nth(n) {
+        Some((index, character)) => &s[index..index + character.len_utf8()],
+        None => end_of(s),
+    }
+}
+
+/// Parses every line with [`FromStr`], stopping at the first error.
+pub fn lines<T>(input: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr<Err = Error>,
+{
+    input.lines().map(str::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number, split_once, Error, Position};
+
+    #[test]
+    fn test_locate_token() {
+        let input = "498,4 -> 498,6\n503,4 -> 50x,4";
+        let token = &input[24..27];
+
+        let err = number::<u32>(token).unwrap_err().in_day(14).locate(input);
+
+        assert_eq!(
+            err.position,
+            Some(Position {
+                line: 2,
+                column: 10,
+                text: String::from("503,4 -> 50x,4"),
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "day 14, line 2, column 10: invalid number `50x`: invalid digit found in string\n    \
+             2 | 503,4 -> 50x,4\n      |          ^"
+        );
+    }
+
+    #[test]
+    fn test_missing_delimiter_points_behind_token() {
+        let input = "Sensor at x=2 y=18";
+
+        let err = split_once(input, ",").unwrap_err().locate(input);
+
+        assert_eq!(err.position.unwrap().column, input.len() + 1);
+    }
+
+    #[test]
+    fn test_foreign_token_is_not_located() {
+        let copy = String::from("7");
+
+        let err = Error::at(&copy, "unexpected").locate("1\n2\n7");
+
+        assert_eq!(err.position, None);
+        assert_eq!(err.to_string(), "unexpected");
+    }
+}
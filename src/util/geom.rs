@@ -0,0 +1,485 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::error::{self, Error};
+
+/// The integer types coordinates can be made of.
+pub trait Number:
+    Copy
+    + Default
+    + Ord
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The distance between two numbers, which does not underflow for unsigned types.
+    fn distance(self, other: Self) -> Self {
+        match self > other {
+            true => self - other,
+            false => other - self,
+        }
+    }
+}
+
+macro_rules! number {
+    ($($t:ty),*) => {
+        $(impl Number for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/**
+ * A position on a 2D map. Like on the puzzle maps, `y` grows downwards, so "up" is the
+ * negative `y` direction and turning right goes clockwise.
+ *
+ * Positions are moved by adding a [`Vector`], and the difference of two positions is one.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// The offset between two [`Point`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A position in space, see [`Point`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// The offset between two [`Point3`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Number> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    /// The number of orthogonal steps to `other`.
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// The number of steps to `other` if diagonal steps are allowed.
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Point<T> {
+    /// The positions up, right, down and left.
+    pub fn neighbours(self) -> [Self; 4] {
+        Direction::ALL.map(|direction| self + direction.vector())
+    }
+
+    /// Clockwise, starting at the top left.
+    pub fn surrounding(self) -> [Self; 8] {
+        let (one, zero) = (T::ONE, T::ZERO);
+        [
+            (-one, -one),
+            (zero, -one),
+            (one, -one),
+            (one, zero),
+            (one, one),
+            (zero, one),
+            (-one, one),
+            (-one, zero),
+        ]
+        .map(|(x, y)| self + Vector::new(x, y))
+    }
+}
+
+impl<T: Number> Vector<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Vector { x, y }
+    }
+
+    /// The length in orthogonal steps.
+    pub fn manhattan(self) -> T {
+        self.x.distance(T::ZERO) + self.y.distance(T::ZERO)
+    }
+
+    /// The length if diagonal steps are allowed.
+    pub fn chebyshev(self) -> T {
+        self.x.distance(T::ZERO).max(self.y.distance(T::ZERO))
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Vector<T> {
+    /// A quarter turn clockwise on the map, up becomes right.
+    pub fn rotate_right(self) -> Self {
+        Vector::new(-self.y, self.x)
+    }
+
+    /// A quarter turn counterclockwise on the map, up becomes left.
+    pub fn rotate_left(self) -> Self {
+        Vector::new(self.y, -self.x)
+    }
+}
+
+impl<T: Number> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x
+            .distance(other.x)
+            .max(self.y.distance(other.y))
+            .max(self.z.distance(other.z))
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Point3<T> {
+    /// The positions that share a face with this one.
+    pub fn neighbours(self) -> [Self; 6] {
+        Vector3::units().map(|unit| self + unit)
+    }
+}
+
+impl<T: Number> Vector3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Vector3 { x, y, z }
+    }
+
+    pub fn manhattan(self) -> T {
+        self.x.distance(T::ZERO) + self.y.distance(T::ZERO) + self.z.distance(T::ZERO)
+    }
+
+    pub fn chebyshev(self) -> T {
+        self.x
+            .distance(T::ZERO)
+            .max(self.y.distance(T::ZERO))
+            .max(self.z.distance(T::ZERO))
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Vector3<T> {
+    /// One step along each axis, in both directions.
+    pub fn units() -> [Self; 6] {
+        let (one, zero) = (T::ONE, T::ZERO);
+        [
+            Vector3::new(one, zero, zero),
+            Vector3::new(-one, zero, zero),
+            Vector3::new(zero, one, zero),
+            Vector3::new(zero, -one, zero),
+            Vector3::new(zero, zero, one),
+            Vector3::new(zero, zero, -one),
+        ]
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// A quarter turn around `axis`, counterclockwise when looking at the origin from its
+    /// positive side.
+    pub fn rotate(self, axis: Axis) -> Self {
+        let Vector3 { x, y, z } = self;
+        match axis {
+            Axis::X => Vector3::new(x, -z, y),
+            Axis::Y => Vector3::new(z, y, -x),
+            Axis::Z => Vector3::new(-y, x, z),
+        }
+    }
+}
+
+/// The four directions on a map, clockwise from up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    /// One step in this direction.
+    pub fn vector<T: Number + Neg<Output = T>>(self) -> Vector<T> {
+        let (one, zero) = (T::ONE, T::ZERO);
+        match self {
+            Direction::Up => Vector::new(zero, -one),
+            Direction::Right => Vector::new(one, zero),
+            Direction::Down => Vector::new(zero, one),
+            Direction::Left => Vector::new(-one, zero),
+        }
+    }
+}
+
+/// The axes in space, to rotate a [`Vector3`] around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Implements the arithmetic between a point type and its vector type.
+macro_rules! arithmetic {
+    ($point:ident, $vector:ident, $($field:ident),+) => {
+        impl<T: Number> Add<$vector<T>> for $point<T> {
+            type Output = Self;
+
+            fn add(self, vector: $vector<T>) -> Self {
+                $point { $($field: self.$field + vector.$field),+ }
+            }
+        }
+
+        impl<T: Number> Sub<$vector<T>> for $point<T> {
+            type Output = Self;
+
+            fn sub(self, vector: $vector<T>) -> Self {
+                $point { $($field: self.$field - vector.$field),+ }
+            }
+        }
+
+        impl<T: Number> AddAssign<$vector<T>> for $point<T> {
+            fn add_assign(&mut self, vector: $vector<T>) {
+                $(self.$field += vector.$field;)+
+            }
+        }
+
+        impl<T: Number> SubAssign<$vector<T>> for $point<T> {
+            fn sub_assign(&mut self, vector: $vector<T>) {
+                $(self.$field -= vector.$field;)+
+            }
+        }
+
+        impl<T: Number> Sub for $point<T> {
+            type Output = $vector<T>;
+
+            fn sub(self, other: Self) -> $vector<T> {
+                $vector { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Number> Add for $vector<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $vector { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Number> Sub for $vector<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $vector { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Number> AddAssign for $vector<T> {
+            fn add_assign(&mut self, other: Self) {
+                $(self.$field += other.$field;)+
+            }
+        }
+
+        impl<T: Number> SubAssign for $vector<T> {
+            fn sub_assign(&mut self, other: Self) {
+                $(self.$field -= other.$field;)+
+            }
+        }
+
+        impl<T: Number> Mul<T> for $vector<T> {
+            type Output = Self;
+
+            fn mul(self, factor: T) -> Self {
+                $vector { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl<T: Number + Neg<Output = T>> Neg for $vector<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $vector { $($field: -self.$field),+ }
+            }
+        }
+
+        /// Comma separated coordinates, like `2,-3`.
+        impl<T: Number + FromStr> FromStr for $point<T>
+        where
+            T::Err: fmt::Display,
+        {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut parts = s.trim().split(',');
+                $(let $field = error::number(error::next(&mut parts, s, stringify!($field))?.trim())?;)+
+
+                match parts.next() {
+                    None => Ok($point { $($field),+ }),
+                    Some(rest) => Err(Error::at(rest, "too many coordinates")),
+                }
+            }
+        }
+    };
+}
+
+arithmetic!(Point, Vector, x, y);
+arithmetic!(Point3, Vector3, x, y, z);
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis, Direction, Point, Point3, Vector, Vector3};
+
+    #[test]
+    fn test_arithmetic() {
+        let mut point = Point::new(2, 3);
+        point += Vector::new(-1, 1) * 3;
+
+        assert_eq!(point, Point::new(-1, 6));
+        assert_eq!(point - Point::new(2, 3), Vector::new(-3, 3));
+        assert_eq!(point - Vector::new(1, 1), Point::new(-2, 5));
+        assert_eq!(-Vector::new(1, -2), Vector::new(-1, 2));
+        assert_eq!(
+            Point3::new(1, 2, 3) + Vector3::new(1, 1, 1) + Vector3::new(0, 0, 1),
+            Point3::new(2, 3, 5)
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(8, 7), Point::new(2, 10));
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!((a - b).manhattan(), 9);
+
+        // Unsigned coordinates do not underflow.
+        assert_eq!(Point::new(1usize, 5).manhattan(Point::new(4, 2)), 6);
+
+        let (a, b) = (Point3::new(1, -1, 0), Point3::new(-2, 1, 4));
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(a.chebyshev(b), 4);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let up: Vector<i32> = Direction::Up.vector();
+        assert_eq!(up.rotate_right(), Direction::Right.vector());
+        assert_eq!(up.rotate_left(), Direction::Left.vector());
+        assert_eq!(
+            Vector::new(2, 1).rotate_right().rotate_left(),
+            Vector::new(2, 1)
+        );
+
+        let x = Vector3::new(1, 0, 0);
+        assert_eq!(x.rotate(Axis::Z), Vector3::new(0, 1, 0));
+        assert_eq!(x.rotate(Axis::Y), Vector3::new(0, 0, -1));
+        assert_eq!(x.rotate(Axis::X), x);
+        assert_eq!(x.cross(x.rotate(Axis::Z)), Vector3::new(0, 0, 1));
+    }
+
+    #[test]
+    fn test_directions() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(
+                direction.opposite().vector::<i8>(),
+                -direction.vector::<i8>()
+            );
+        }
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+
+        assert_eq!(
+            Point::new(0, 0).neighbours(),
+            [
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ]
+        );
+        assert_eq!(Point::new(0, 0).surrounding()[0], Point::new(-1, -1));
+        assert_eq!(Point3::new(0, 0, 0).neighbours().len(), 6);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("498,4".parse(), Ok(Point::new(498, 4)));
+        assert_eq!(" 2, -3 ".parse(), Ok(Point::new(2, -3)));
+        assert_eq!("1,2,3".parse(), Ok(Point3::new(1u8, 2, 3)));
+        assert!("1,2,3".parse::<Point<i32>>().is_err());
+        assert!("1".parse::<Point<i32>>().is_err());
+    }
+}
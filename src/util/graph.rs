@@ -0,0 +1,330 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// A shortest path, with the start and the goal included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub nodes: Vec<N>,
+    pub cost: u64,
+}
+
+/**
+ * The nodes a search has discovered. Nodes are stored once and referred to by index, so
+ * the queues only move numbers around and nodes do not need to be `Ord`.
+ */
+struct Explored<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    /// The node a node was first reached from, the start points to itself.
+    parents: Vec<usize>,
+    costs: Vec<u64>,
+}
+
+impl<N: Clone + Eq + Hash> Explored<N> {
+    fn new(start: N) -> Self {
+        Explored {
+            nodes: vec![start.clone()],
+            index: HashMap::from([(start, 0)]),
+            parents: vec![0],
+            costs: vec![0],
+        }
+    }
+
+    /// Returns the index of the node, if it was new or is cheaper to reach now.
+    fn reach(&mut self, node: N, parent: usize, cost: u64) -> Option<usize> {
+        match self.index.get(&node) {
+            Some(&i) if self.costs[i] <= cost => None,
+            Some(&i) => {
+                self.parents[i] = parent;
+                self.costs[i] = cost;
+                Some(i)
+            }
+            None => {
+                let i = self.nodes.len();
+                self.index.insert(node.clone(), i);
+                self.nodes.push(node);
+                self.parents.push(parent);
+                self.costs.push(cost);
+                Some(i)
+            }
+        }
+    }
+
+    fn path_to(&self, mut i: usize) -> Path<N> {
+        let cost = self.costs[i];
+        let mut nodes = vec![self.nodes[i].clone()];
+
+        while self.parents[i] != i {
+            i = self.parents[i];
+            nodes.push(self.nodes[i].clone());
+        }
+        nodes.reverse();
+
+        Path { nodes, cost }
+    }
+}
+
+/// Shortest path if every step costs 1, to the first node that is a goal.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut explored = Explored::new(start);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&explored.nodes[current]) {
+            return Some(explored.path_to(current));
+        }
+
+        let cost = explored.costs[current] + 1;
+        for neighbour in neighbours(&explored.nodes[current]) {
+            if explored.index.contains_key(&neighbour) {
+                continue;
+            }
+            if let Some(next) = explored.reach(neighbour, current, cost) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * Shortest path with weighted steps. `heuristic` estimates the remaining cost to a goal
+ * and must never overestimate it, or the path found may not be the shortest.
+ */
+pub fn astar<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut explored = Explored::new(start);
+    let mut queue = BinaryHeap::from([(Reverse(heuristic(&explored.nodes[0])), 0, 0)]);
+
+    while let Some((_, cost, current)) = queue.pop() {
+        // Outdated, the node was reached on a cheaper path since.
+        if cost > explored.costs[current] {
+            continue;
+        }
+
+        if is_goal(&explored.nodes[current]) {
+            return Some(explored.path_to(current));
+        }
+
+        for (neighbour, step) in neighbours(&explored.nodes[current]) {
+            let cost = cost + step;
+            let estimate = cost + heuristic(&neighbour);
+
+            if let Some(next) = explored.reach(neighbour, current, cost) {
+                queue.push((Reverse(estimate), cost, next));
+            }
+        }
+    }
+
+    None
+}
+
+/// Shortest path with weighted steps, [`astar`] without a heuristic.
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// Shortest paths between all pairs of nodes, see [`all_pairs`].
+#[derive(Debug, Clone)]
+pub struct AllPairs<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    costs: Vec<Vec<Option<u64>>>,
+    /// The second node on the shortest path from one node to another.
+    next: Vec<Vec<usize>>,
+}
+
+impl<N: Clone + Eq + Hash> AllPairs<N> {
+    pub fn cost(&self, from: &N, to: &N) -> Option<u64> {
+        self.costs[*self.index.get(from)?][*self.index.get(to)?]
+    }
+
+    pub fn path(&self, from: &N, to: &N) -> Option<Path<N>> {
+        let (mut i, to) = (*self.index.get(from)?, *self.index.get(to)?);
+        let cost = self.costs[i][to]?;
+
+        let mut nodes = vec![self.nodes[i].clone()];
+        while i != to {
+            i = self.next[i][to];
+            nodes.push(self.nodes[i].clone());
+        }
+
+        Some(Path { nodes, cost })
+    }
+}
+
+/**
+ * Floyd-Warshall over `nodes`, neighbours that are not in `nodes` are ignored. Takes cubic
+ * time in the number of nodes, which is fine for the few dozen that puzzles usually have.
+ */
+pub fn all_pairs<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> AllPairs<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let nodes: Vec<N> = nodes.into_iter().collect();
+    let index: HashMap<N, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.clone(), i))
+        .collect();
+
+    let len = nodes.len();
+    let mut costs = vec![vec![None; len]; len];
+    let mut next: Vec<Vec<usize>> = (0..len).map(|_| (0..len).collect()).collect();
+
+    for (from, node) in nodes.iter().enumerate() {
+        costs[from][from] = Some(0);
+
+        for (neighbour, step) in neighbours(node) {
+            let Some(&to) = index.get(&neighbour) else {
+                continue;
+            };
+            if costs[from][to].is_none_or(|cost| step < cost) {
+                costs[from][to] = Some(step);
+                next[from][to] = to;
+            }
+        }
+    }
+
+    for via in 0..len {
+        for from in 0..len {
+            let Some(first) = costs[from][via] else {
+                continue;
+            };
+            for to in 0..len {
+                let Some(second) = costs[via][to] else {
+                    continue;
+                };
+                if costs[from][to].is_none_or(|cost| first + second < cost) {
+                    costs[from][to] = Some(first + second);
+                    next[from][to] = next[from][via];
+                }
+            }
+        }
+    }
+
+    AllPairs {
+        nodes,
+        index,
+        costs,
+        next,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all_pairs, astar, bfs, dijkstra, Path};
+
+    /// `a` to `e` in a line with a costly shortcut from `a` to `d`.
+    fn edges(node: &char) -> Vec<(char, u64)> {
+        match node {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('a', 1), ('c', 1)],
+            'c' => vec![('b', 1), ('d', 1)],
+            'd' => vec![('c', 1), ('e', 1), ('a', 5)],
+            'e' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let neighbours = |node: &char| edges(node).into_iter().map(|(node, _)| node);
+
+        assert_eq!(
+            bfs('a', neighbours, |node| *node == 'e'),
+            Some(Path {
+                nodes: vec!['a', 'd', 'e'],
+                cost: 2
+            })
+        );
+        assert_eq!(
+            bfs('a', neighbours, |node| *node == 'a').map(|path| path.nodes),
+            Some(vec!['a'])
+        );
+        assert_eq!(bfs('a', neighbours, |node| *node == 'x'), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(
+            dijkstra('a', edges, |node| *node == 'e'),
+            Some(Path {
+                nodes: vec!['a', 'b', 'c', 'd', 'e'],
+                cost: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_astar_on_grid() {
+        // Around a wall at x = 2 from y = 0 to y = 3.
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| (x + dx, y + dy))
+                .filter(|(x, y)| (0..5).contains(x) && (0..5).contains(y))
+                .filter(|(x, y)| *x != 2 || *y == 4)
+                .map(|node| (node, 1))
+        };
+        let goal = (4, 0);
+        let manhattan =
+            |(x, y): &(i32, i32)| (goal.0 - x).unsigned_abs() as u64 + y.unsigned_abs() as u64;
+
+        let path = astar((0, 0), neighbours, manhattan, |node| *node == goal).unwrap();
+
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.len(), 13);
+        assert!(path.nodes.contains(&(2, 4)));
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let pairs = all_pairs("abcde".chars(), edges);
+
+        assert_eq!(pairs.cost(&'a', &'e'), Some(4));
+        assert_eq!(pairs.cost(&'e', &'a'), Some(4));
+        assert_eq!(pairs.cost(&'c', &'c'), Some(0));
+        assert_eq!(pairs.cost(&'a', &'x'), None);
+        assert_eq!(
+            pairs.path(&'e', &'b').map(|path| path.nodes),
+            Some(vec!['e', 'd', 'c', 'b'])
+        );
+
+        let disconnected = all_pairs("ax".chars(), edges);
+        assert_eq!(disconnected.cost(&'a', &'x'), None);
+        assert_eq!(disconnected.path(&'a', &'x'), None);
+    }
+}
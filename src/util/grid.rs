@@ -0,0 +1,305 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use super::Error;
+
+/// Up, right, down and left.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// Clockwise, starting at the top left.
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/**
+ * A dense, rectangular grid, indexed by `(x, y)` with `(0, 0)` in the top left corner.
+ *
+ * Cells are stored row by row, so a row is a slice and walking along it stays in the cache.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /**
+     * Parses a character map, one line per row and one character per cell. `cell` returns
+     * `None` for characters that are not allowed, which is reported at their position.
+     */
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, Error> {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(Error::new("the map is empty"));
+        }
+
+        let mut cells = Vec::with_capacity(input.len());
+        let mut height = 0;
+
+        for line in input.lines() {
+            for (index, c) in line.char_indices() {
+                match cell(c) {
+                    Some(value) => cells.push(value),
+                    None => {
+                        let token = &line[index..index + c.len_utf8()];
+                        return Err(Error::at(token, format!("unexpected `{c}` in the map")));
+                    }
+                }
+            }
+
+            height += 1;
+            if cells.len() != width * height {
+                return Err(Error::at(line, format!("expected {width} columns")));
+            }
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        match self.contains(x, y) {
+            true => Some(&mut self.cells[y * self.width + x]),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside of the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The position of the first cell, row by row, that matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(*dx)?;
+            let y = y.checked_add_signed(*dy)?;
+            (x < width && y < height).then_some((x, y))
+        })
+    }
+
+    /**
+     * The positions up, right, down and left of `(x, y)` that are inside the grid.
+     *
+     * Only the size of the grid is borrowed, so cells can be changed while iterating.
+     */
+    pub fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// Like [`Grid::neighbours`], including the diagonals.
+    pub fn surrounding(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &SURROUNDING)
+    }
+
+    /// Renders every cell as a character, one line per row.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut cell));
+            out.push('\n');
+        }
+        out.pop();
+
+        out
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside of the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    static MAP: &str = "\
+#..
+.#.
+..#
+##.";
+
+    fn parse() -> Grid<bool> {
+        Grid::parse(MAP, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_access() {
+        let grid = parse();
+
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert!(grid[(1, 1)]);
+        assert!(!grid[(1, 0)]);
+        assert_eq!(grid.get(2, 3), Some(&false));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 4), None);
+        assert_eq!(grid.position(|cell| !cell), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_invalid_map() {
+        let input = "#.\n#x";
+        let err = Grid::parse(input, |c| (c == '#' || c == '.').then_some(c))
+            .unwrap_err()
+            .locate(input);
+        let position = err.position.unwrap();
+        assert_eq!((position.line, position.column), (2, 2));
+
+        assert!(Grid::parse("##\n#", Some).is_err());
+        assert!(Grid::parse("", Some).is_err());
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = parse();
+
+        assert_eq!(grid.row(3), &[true, true, false]);
+        assert_eq!(
+            grid.column(1).copied().collect::<Vec<_>>(),
+            vec![false, true, false, true]
+        );
+        assert_eq!(grid.rows().count(), 4);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = parse();
+
+        assert_eq!(
+            grid.neighbours(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours(1, 1).count(), 4);
+        assert_eq!(grid.surrounding(1, 1).count(), 8);
+        assert_eq!(
+            grid.surrounding(2, 3).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = parse();
+
+        assert_eq!(grid.render(|cell| if *cell { '#' } else { '.' }), MAP);
+        assert_eq!(
+            grid.map(|cell| u8::from(*cell)).to_string(),
+            "100\n010\n001\n110"
+        );
+    }
+}
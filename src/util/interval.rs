@@ -0,0 +1,262 @@
+use super::geom::Number;
+
+/**
+ * The numbers from `start` up to, but not including, `end`. Puzzles usually give the last
+ * number instead, [`Interval::inclusive`] takes that.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Number> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    /// From `first` to `last`, both included.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Interval::new(first, last + T::ONE)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        match self.is_empty() {
+            true => T::ZERO,
+            false => self.end - self.start,
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every number of `other` is in this interval, an empty one always is.
+    pub fn covers(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The numbers in both intervals, which may be empty.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+/**
+ * A set of numbers, stored as the sorted list of intervals it is made of. Intervals that
+ * overlap or touch are merged as they are inserted, so the set stays as small as the
+ * numbers allow and lookups are binary searches.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Number> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Adds the numbers of `interval` to the set.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Everything from `first` to `last` overlaps or touches the new interval.
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end);
+
+        let mut merged = interval;
+        if first < last {
+            merged.start = merged.start.min(self.intervals[first].start);
+            merged.end = merged.end.max(self.intervals[last - 1].end);
+        }
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// The disjoint intervals of the set, in ascending order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of numbers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |len, interval| len + interval.len())
+    }
+
+    /// The interval that contains `value` or is the first one after it.
+    fn find(&self, value: T) -> Option<&Interval<T>> {
+        let index = self.intervals.partition_point(|other| other.end <= value);
+        self.intervals.get(index)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.find(value)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Whether every number of `interval` is in the set.
+    pub fn covers(&self, interval: &Interval<T>) -> bool {
+        interval.is_empty()
+            || self
+                .find(interval.start)
+                .is_some_and(|other| other.covers(interval))
+    }
+
+    /// Whether any number of `interval` is in the set.
+    pub fn overlaps(&self, interval: &Interval<T>) -> bool {
+        !interval.is_empty()
+            && self
+                .find(interval.start)
+                .is_some_and(|other| other.overlaps(interval))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = IntervalSet::new();
+        let (mut left, mut right) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.intervals.get(left), other.intervals.get(right)) {
+            let overlap = a.intersection(b);
+            if !overlap.is_empty() {
+                intersection.intervals.push(overlap);
+            }
+
+            // The interval that ends first cannot overlap anything else on the other side.
+            match a.end < b.end {
+                true => left += 1,
+                false => right += 1,
+            }
+        }
+
+        intersection
+    }
+
+    /// The numbers in `within` that are not in the set.
+    pub fn gaps(&self, within: Interval<T>) -> Self {
+        let mut gaps = IntervalSet::new();
+        let mut start = within.start;
+
+        for interval in &self.intervals {
+            if interval.start >= within.end {
+                break;
+            }
+            if interval.start > start {
+                gaps.intervals.push(Interval::new(start, interval.start));
+            }
+            start = start.max(interval.end);
+        }
+        if start < within.end {
+            gaps.intervals.push(Interval::new(start, within.end));
+        }
+
+        gaps
+    }
+}
+
+impl<T: Number> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, IntervalSet};
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|&(first, last)| Interval::inclusive(first, last))
+            .collect()
+    }
+
+    #[test]
+    fn test_intervals() {
+        let (a, b) = (Interval::inclusive(2, 8), Interval::inclusive(3, 7));
+
+        assert_eq!(a.len(), 7);
+        assert!(a.covers(&b) && !b.covers(&a));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&Interval::inclusive(9, 9)));
+        assert_eq!(a.intersection(&Interval::new(-4, 4)), Interval::new(2, 4));
+        assert!(Interval::new(5, 5).is_empty());
+        assert_eq!(Interval::new(5, 1).len(), 0);
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        // Overlapping and touching intervals are merged, the gap at 6 is kept.
+        let set = set(&[(12, 12), (-2, 2), (2, 2), (3, 5), (7, 10), (11, 14)]);
+
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(-2, 6), Interval::new(7, 15)]
+        );
+        assert_eq!(set.len(), 16);
+        assert!(set.contains(-2) && set.contains(14));
+        assert!(!set.contains(6) && !set.contains(15));
+        assert!(set.covers(&Interval::inclusive(8, 14)));
+        assert!(!set.covers(&Interval::inclusive(4, 8)));
+        assert!(set.overlaps(&Interval::inclusive(4, 8)));
+        assert!(!set.overlaps(&Interval::inclusive(6, 6)));
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let a = set(&[(0, 4), (10, 14)]);
+        let b = set(&[(3, 11), (20, 20)]);
+
+        assert_eq!(a.union(&b), set(&[(0, 14), (20, 20)]));
+        assert_eq!(a.intersection(&b), set(&[(3, 4), (10, 11)]));
+        assert!(a.intersection(&set(&[(5, 9)])).is_empty());
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set = set(&[(-2, 2), (5, 5), (8, 30)]);
+
+        assert_eq!(
+            set.gaps(Interval::inclusive(0, 20)),
+            self::set(&[(3, 4), (6, 7)])
+        );
+        assert_eq!(
+            set.gaps(Interval::inclusive(-5, 3)),
+            self::set(&[(-5, -3), (3, 3)])
+        );
+        assert!(set.gaps(Interval::inclusive(9, 20)).is_empty());
+    }
+}
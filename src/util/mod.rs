@@ -1,15 +1,46 @@
-use std::time::Instant;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
 
-pub fn with_timing<F, T>(f: F) -> T
+pub mod circle;
+pub mod error;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod parse;
+pub mod sparse;
+
+pub use error::Error;
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Like `eprintln!`, but only with `--verbose`, so debug output never mixes with the answers.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::util::verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+pub fn measure<F, T>(f: F) -> (T, Duration)
 where
-    F: Fn() -> T,
+    F: FnOnce() -> T,
 {
     let now = Instant::now();
 
     let result = f();
 
-    let elapsed = now.elapsed();
-    println!("time: {:.2?}", elapsed);
-
-    result
+    (result, now.elapsed())
 }
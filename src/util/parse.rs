@@ -0,0 +1,250 @@
+use std::{fmt, str::FromStr};
+
+use super::error::{self, Error};
+
+/**
+ * A cursor over a slice of the puzzle input. Every step skips the whitespace in front of
+ * it, so specs only list what matters:
+ *
+ * ```text
+ * let mut p = Parser::new(line);
+ * p.literal("move")?;
+ * let amount = p.unsigned()?;
+ * ```
+ *
+ * Errors point at the position of the cursor, so they can be located in the input.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Parser { rest: s }
+    }
+
+    /// What has not been parsed yet, after skipping whitespace.
+    pub fn rest(&mut self) -> &'a str {
+        self.rest = self.rest.trim_start();
+        self.rest
+    }
+
+    fn take(&mut self, len: usize) -> &'a str {
+        let (token, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        token
+    }
+
+    /// Consumes `literal` if it is next.
+    pub fn try_literal(&mut self, literal: &str) -> bool {
+        let found = self.rest().starts_with(literal);
+        if found {
+            self.take(literal.len());
+        }
+        found
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), Error> {
+        match self.try_literal(literal) {
+            true => Ok(()),
+            false => Err(Error::at(self.rest, format!("expected `{literal}`"))),
+        }
+    }
+
+    /// The value of the first literal that is next.
+    pub fn one_of<T: Copy>(&mut self, options: &[(&str, T)]) -> Result<T, Error> {
+        for (literal, value) in options {
+            if self.try_literal(literal) {
+                return Ok(*value);
+            }
+        }
+
+        let literals: Vec<String> = options
+            .iter()
+            .map(|(literal, _)| format!("`{literal}`"))
+            .collect();
+        Err(Error::at(
+            self.rest,
+            format!("expected one of {}", literals.join(", ")),
+        ))
+    }
+
+    fn digits(&mut self, sign: bool) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        let start = match rest.as_bytes().first() {
+            Some(b'-' | b'+') if sign => 1,
+            _ => 0,
+        };
+        let len = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - start);
+
+        match len {
+            0 => Err(Error::at(rest, "expected a number")),
+            len => Ok(self.take(start + len)),
+        }
+    }
+
+    pub fn unsigned<T>(&mut self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        error::number(self.digits(false)?)
+    }
+
+    /// Like [`Parser::unsigned`], with an optional `-` or `+` in front.
+    pub fn signed<T>(&mut self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        error::number(self.digits(true)?)
+    }
+
+    /// Letters, digits and underscores, like a name or a keyword.
+    pub fn word(&mut self) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+
+        match len {
+            0 => Err(Error::at(rest, "expected a word")),
+            len => Ok(self.take(len)),
+        }
+    }
+
+    /// Everything up to `delimiter`, which is consumed but not returned.
+    pub fn until(&mut self, delimiter: &str) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        let (token, _) = error::split_once(rest, delimiter)?;
+        self.take(token.len() + delimiter.len());
+        Ok(token)
+    }
+
+    /// At least one `item`, separated by `separator`.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let mut items = vec![item(self)?];
+
+        while self.try_literal(separator) {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    /// Fails if anything but whitespace is left.
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.rest() {
+            "" => Ok(()),
+            rest => Err(Error::at(rest, format!("unexpected `{rest}`"))),
+        }
+    }
+}
+
+/// Parses every line with `spec`, which has to consume the whole line.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut spec: impl FnMut(&mut Parser<'a>) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    input
+        .lines()
+        .map(|line| {
+            let mut parser = Parser::new(line);
+            let value = spec(&mut parser)?;
+            parser.end()?;
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Groups of lines separated by one or more blank lines.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|block| block.trim_matches('\n'))
+        .filter(|block| !block.is_empty())
+}
+
+/// `key<separator>value`, both trimmed.
+pub fn key_value<'a>(line: &'a str, separator: &str) -> Result<(&'a str, &'a str), Error> {
+    let (key, value) = error::split_once(line, separator)?;
+    Ok((key.trim(), value.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, key_value, lines, Parser};
+
+    #[test]
+    fn test_spec() {
+        let line = "Valve AA has flow rate=-13; tunnels lead to valves DD, II, BB";
+
+        let mut p = Parser::new(line);
+        p.literal("Valve").unwrap();
+        assert_eq!(p.word(), Ok("AA"));
+        p.literal("has flow rate=").unwrap();
+        assert_eq!(p.signed::<i32>(), Ok(-13));
+        assert_eq!(p.until(";"), Ok(""));
+        assert_eq!(
+            p.one_of(&[("tunnel leads to valve", 1), ("tunnels lead to valves", 2)]),
+            Ok(2)
+        );
+        assert_eq!(p.separated(",", Parser::word), Ok(vec!["DD", "II", "BB"]));
+        assert!(p.end().is_ok());
+    }
+
+    #[test]
+    fn test_errors_point_at_the_cursor() {
+        let input = "move 3 from 1 to x";
+
+        let err = lines(input, |p| {
+            p.literal("move")?;
+            let amount: usize = p.unsigned()?;
+            p.literal("from")?;
+            let from: usize = p.unsigned()?;
+            p.literal("to")?;
+            let to: usize = p.unsigned()?;
+            Ok((amount, from, to))
+        })
+        .unwrap_err()
+        .locate(input);
+
+        assert_eq!(err.message, "expected a number");
+        assert_eq!(err.position.unwrap().column, 18);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let mut p = Parser::new("+7 -0 12x 300");
+
+        assert_eq!(p.signed::<i8>(), Ok(7));
+        assert_eq!(p.signed::<i8>(), Ok(0));
+        assert_eq!(p.unsigned::<u8>(), Ok(12));
+        assert!(p.unsigned::<u8>().is_err());
+        p.literal("x").unwrap();
+        assert!(p.unsigned::<u8>().is_err());
+        assert!(Parser::new("-1").unsigned::<u32>().is_err());
+    }
+
+    #[test]
+    fn test_blocks_and_key_values() {
+        let input = "a: 1\nb: 2\n\n\nc: 3\n";
+
+        assert_eq!(
+            blocks(input).collect::<Vec<_>>(),
+            vec!["a: 1\nb: 2", "c: 3"]
+        );
+        assert_eq!(
+            key_value("root: pppw + sjmn", ":"),
+            Ok(("root", "pppw + sjmn"))
+        );
+        assert!(key_value("root", ":").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+};
+
+/// Chunks are `SIZE * SIZE` cells, `SIZE` is `1 << SHIFT`.
+const SHIFT: u32 = 4;
+const SIZE: i64 = 1 << SHIFT;
+
+/**
+ * Chunk coordinates are small and not attacker controlled, so a multiply is enough of a
+ * hash and much cheaper than the default one on the hot paths of the simulations.
+ */
+#[derive(Default)]
+struct ChunkHasher(u64);
+
+impl Hasher for ChunkHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u64(u64::from(*byte));
+        }
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+/// The smallest rectangle around all set cells, inclusive on both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    pub fn width(&self) -> u64 {
+        self.max_x.abs_diff(self.min_x) + 1
+    }
+
+    pub fn height(&self) -> u64 {
+        self.max_y.abs_diff(self.min_y) + 1
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width() * self.height()
+    }
+
+    fn include(&mut self, x: i64, y: i64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+}
+
+/**
+ * An unbounded grid with signed coordinates, for simulations that wander off in any
+ * direction. Every cell exists and starts out as `T::default()`, which is the only value
+ * that does not count as set.
+ *
+ * Cells are allocated in square chunks as they are written, so neighbouring cells are
+ * usually in the same allocation.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct SparseGrid<T> {
+    chunks: HashMap<(i64, i64), Box<[T]>, BuildHasherDefault<ChunkHasher>>,
+    default: T,
+}
+
+impl<T: Clone + Default + PartialEq> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            chunks: HashMap::default(),
+            default: T::default(),
+        }
+    }
+
+    fn locate(x: i64, y: i64) -> ((i64, i64), usize) {
+        let chunk = (x >> SHIFT, y >> SHIFT);
+        let index = ((y & (SIZE - 1)) * SIZE + (x & (SIZE - 1))) as usize;
+        (chunk, index)
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        let (chunk, index) = Self::locate(x, y);
+
+        match self.chunks.get(&chunk) {
+            Some(cells) => &cells[index],
+            None => &self.default,
+        }
+    }
+
+    /// Grows the grid if the cell is not allocated yet.
+    pub fn get_mut(&mut self, x: i64, y: i64) -> &mut T {
+        let (chunk, index) = Self::locate(x, y);
+
+        let cells = self
+            .chunks
+            .entry(chunk)
+            .or_insert_with(|| vec![T::default(); (SIZE * SIZE) as usize].into_boxed_slice());
+        &mut cells[index]
+    }
+
+    /// Returns the previous value.
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> T {
+        // Clearing a cell that was never set does not need a chunk.
+        if value == self.default && *self.get(x, y) == self.default {
+            return value;
+        }
+
+        std::mem::replace(self.get_mut(x, y), value)
+    }
+
+    /// All set cells with their position, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.chunks
+            .iter()
+            .flat_map(move |((chunk_x, chunk_y), cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, cell)| **cell != self.default)
+                    .map(move |(index, cell)| {
+                        let x = chunk_x * SIZE + index as i64 % SIZE;
+                        let y = chunk_y * SIZE + index as i64 / SIZE;
+                        ((x, y), cell)
+                    })
+            })
+    }
+
+    /// The number of set cells.
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Looks at every allocated cell, cells that were cleared again do not count.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut positions = self.iter().map(|(position, _)| position);
+
+        let (x, y) = positions.next()?;
+        let mut bounds = Bounds {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        };
+        for (x, y) in positions {
+            bounds.include(x, y);
+        }
+
+        Some(bounds)
+    }
+
+    /**
+     * Renders the cells inside the bounds, one line per row. Nothing is rendered for an
+     * empty grid.
+     */
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let Some(bounds) = self.bounds() else {
+            return String::new();
+        };
+
+        (bounds.min_y..=bounds.max_y)
+            .map(|y| {
+                (bounds.min_x..=bounds.max_x)
+                    .map(|x| cell(self.get(x, y)))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bounds, SparseGrid};
+
+    #[test]
+    fn test_get_and_set() {
+        let mut grid: SparseGrid<bool> = SparseGrid::new();
+
+        assert!(!grid.get(3, -7));
+        assert!(!grid.set(3, -7, true));
+        assert!(*grid.get(3, -7));
+        assert!(!grid.get(-3, 7));
+
+        *grid.get_mut(-100_000, 100_000) = true;
+        assert!(*grid.get(-100_000, 100_000));
+        assert_eq!(grid.count(), 2);
+
+        assert!(grid.set(3, -7, false));
+        assert_eq!(grid.count(), 1);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        for (x, y) in [(-2, 1), (5, -3), (0, 0), (40, 2)] {
+            grid.set(x, y, 1u8);
+        }
+        // Cleared cells shrink the bounds again.
+        grid.set(40, 2, 0);
+
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                min_x: -2,
+                min_y: -3,
+                max_x: 5,
+                max_y: 1
+            }
+        );
+        assert_eq!((bounds.width(), bounds.height(), bounds.area()), (8, 5, 40));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = SparseGrid::new();
+        grid.set(-1, -1, true);
+        grid.set(1, 0, true);
+
+        assert_eq!(
+            grid.render(|cell| if *cell { '#' } else { '.' }),
+            "#..\n..#"
+        );
+        assert_eq!(SparseGrid::<bool>::new().render(|_| '#'), "");
+    }
+}